@@ -0,0 +1,200 @@
+use crate::{
+    entity::{
+        form::{article_editor, login, register, settings},
+        ApiError, Article, Author, Comment, CommentId, PageNumber, PaginatedList, Slug, Tag,
+        Username, Viewer,
+    },
+    page, storage,
+};
+use std::{
+    cell::RefCell, collections::VecDeque, future::Future, num::NonZeroUsize, pin::Pin, rc::Rc,
+};
+
+pub mod fake;
+pub mod http;
+
+pub use fake::FakeConduit;
+pub use http::HttpConduit;
+
+/// Base API URL which selects the in-memory fake backend instead of a real server.
+pub static FAKE_BASE_URL: &str = "fake";
+
+pub type ApiFuture<T, E = ApiError> = Pin<Box<dyn Future<Output = Result<T, E>>>>;
+
+/// Gets the rejected auth token - see `set_unauthorized_handler`.
+type UnauthorizedHandler = Box<dyn Fn(String)>;
+
+thread_local! {
+    static UNAUTHORIZED_HANDLER: RefCell<Option<UnauthorizedHandler>> = const { RefCell::new(None) };
+}
+
+// ------ ConduitApi ------
+
+/// All Conduit endpoints used by the app.
+///
+/// Pages access the implementation through `Session::api`.
+pub trait ConduitApi {
+    // ------ user ------
+
+    fn login(&self, valid_form: &login::ValidForm) -> ApiFuture<Viewer>;
+
+    fn register(&self, valid_form: &register::ValidForm) -> ApiFuture<Viewer>;
+
+    /// Current state of the stored viewer (e.g. changed in another browser).
+    fn load_viewer(&self, viewer: &Viewer) -> ApiFuture<Viewer>;
+
+    fn load_settings(&self, viewer: Option<&Viewer>) -> ApiFuture<settings::Form>;
+
+    fn update_settings(
+        &self,
+        viewer: Option<&Viewer>,
+        valid_form: &settings::ValidForm,
+    ) -> ApiFuture<Viewer>;
+
+    // ------ profiles ------
+
+    fn load_author(
+        &self,
+        viewer: Option<Viewer>,
+        username: Username<'static>,
+    ) -> ApiFuture<Author, (Username<'static>, ApiError)>;
+
+    fn follow(&self, viewer: Option<Viewer>, username: &Username) -> ApiFuture<Author>;
+
+    fn unfollow(&self, viewer: Option<Viewer>, username: &Username) -> ApiFuture<Author>;
+
+    // ------ articles ------
+
+    fn load_article(&self, viewer: Option<Viewer>, slug: &Slug) -> ApiFuture<Article>;
+
+    fn load_article_for_editor(
+        &self,
+        viewer: Option<Viewer>,
+        slug: Slug,
+    ) -> ApiFuture<Article, (Slug, ApiError)>;
+
+    fn create_article(
+        &self,
+        viewer: Option<Viewer>,
+        valid_form: &article_editor::ValidForm,
+    ) -> ApiFuture<Article>;
+
+    fn update_article(
+        &self,
+        viewer: Option<Viewer>,
+        valid_form: &article_editor::ValidForm,
+        slug: &Slug,
+    ) -> ApiFuture<Article>;
+
+    fn delete_article(&self, viewer: Option<&Viewer>, slug: &Slug) -> ApiFuture<()>;
+
+    fn favorite(&self, viewer: Option<Viewer>, slug: &Slug) -> ApiFuture<Article>;
+
+    fn unfavorite(&self, viewer: Option<Viewer>, slug: &Slug) -> ApiFuture<Article>;
+
+    // ------ feeds ------
+
+    fn load_home_feed(
+        &self,
+        viewer: Option<Viewer>,
+        selected_feed: &page::home::SelectedFeed,
+        page_number: PageNumber,
+        per_page: NonZeroUsize,
+    ) -> ApiFuture<PaginatedList<Article>>;
+
+    fn load_profile_feed(
+        &self,
+        viewer: Option<Viewer>,
+        username: Username<'static>,
+        selected_feed: page::profile::SelectedFeed,
+        page_number: PageNumber,
+        per_page: NonZeroUsize,
+    ) -> ApiFuture<PaginatedList<Article>, (Username<'static>, ApiError)>;
+
+    fn load_tags(&self) -> ApiFuture<Vec<Tag>>;
+
+    // ------ comments ------
+
+    fn load_comments(&self, viewer: Option<Viewer>, slug: &Slug) -> ApiFuture<VecDeque<Comment>>;
+
+    fn create_comment(
+        &self,
+        viewer: Option<Viewer>,
+        slug: &Slug,
+        text: String,
+    ) -> ApiFuture<Comment>;
+
+    fn delete_comment(
+        &self,
+        viewer: Option<&Viewer>,
+        slug: &Slug,
+        comment_id: CommentId,
+    ) -> ApiFuture<CommentId>;
+}
+
+/// Choose the implementation according to the base API URL.
+pub fn for_base_url(base_url: &str) -> Rc<dyn ConduitApi> {
+    if base_url == FAKE_BASE_URL {
+        Rc::new(FakeConduit::with_demo_data())
+    } else {
+        Rc::new(HttpConduit)
+    }
+}
+
+/// Register a callback invoked whenever an API call of a viewer fails with
+/// `ApiError::Unauthorized` - whichever `ConduitApi` implementation is used.
+///
+/// The callback gets the viewer's auth token - the viewer may not be active anymore.
+pub fn set_unauthorized_handler(handler: impl Fn(String) + 'static) {
+    UNAUTHORIZED_HANDLER.with(|old_handler| *old_handler.borrow_mut() = Some(Box::new(handler)));
+}
+
+/// Invoke the unauthorized handler if the server has rejected the viewer's auth token.
+pub fn handle_unauthorized(viewer: Option<&Viewer>, error: &impl CallError) {
+    let auth_token = match (error.api_error(), viewer) {
+        (ApiError::Unauthorized, Some(viewer)) => &viewer.auth_token,
+        _ => return,
+    };
+    UNAUTHORIZED_HANDLER.with(|handler| {
+        if let Some(handler) = handler.borrow().as_ref() {
+            handler(auth_token.clone())
+        }
+    });
+}
+
+/// Convert the API call into a command for `orders.perform_cmd`.
+///
+/// Calls are made on behalf of the active viewer - see `handle_unauthorized`.
+pub fn cmd<T, E: CallError, Ms>(
+    api_future: ApiFuture<T, E>,
+    f: impl FnOnce(Result<T, E>) -> Ms,
+) -> impl Future<Output = Result<Ms, Ms>> {
+    let viewer = storage::load_viewer();
+    async move {
+        let result = api_future.await;
+        if let Err(error) = &result {
+            handle_unauthorized(viewer.as_ref(), error);
+        }
+        Ok(f(result))
+    }
+}
+
+// ------ CallError ------
+
+/// Error of a `ConduitApi` call.
+pub trait CallError {
+    fn api_error(&self) -> &ApiError;
+}
+
+impl CallError for ApiError {
+    fn api_error(&self) -> &ApiError {
+        self
+    }
+}
+
+/// The error with the item it belongs to - e.g. `(Slug, ApiError)`.
+impl<K> CallError for (K, ApiError) {
+    fn api_error(&self) -> &ApiError {
+        &self.1
+    }
+}
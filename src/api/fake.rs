@@ -0,0 +1,888 @@
+use crate::{
+    api::{ApiFuture, ConduitApi},
+    entity::{
+        article::tag::IntoTags,
+        form::{self, article_editor, login, register, settings, FormField},
+        ApiError, Article, Author, Avatar, Comment, CommentId, PageNumber, PaginatedList, Profile,
+        Slug, Tag, Timestamp, Username, Viewer,
+    },
+    page,
+};
+use chrono::Local;
+use indexmap::IndexMap;
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet, VecDeque},
+    future,
+    num::NonZeroUsize,
+    rc::Rc,
+};
+
+type UserId = usize;
+type ArticleId = usize;
+
+// ------ FakeConduit ------
+
+/// In-memory Conduit backend - it lets us run the app and its tests without a server.
+///
+/// Clones share the same data.
+#[derive(Clone, Default)]
+pub struct FakeConduit {
+    db: Rc<RefCell<Db>>,
+}
+
+impl FakeConduit {
+    /// Two users (`demo@example.com` and `jake@example.com`, both with the password "password"),
+    /// their articles and a comment.
+    pub fn with_demo_data() -> Self {
+        let fake_conduit = Self::default();
+        {
+            let mut db = fake_conduit.db.borrow_mut();
+            let demo = db.insert_user("demo", "demo@example.com", "password");
+            let jake = db.insert_user("jake", "jake@example.com", "password");
+            let welcome = db.insert_article(
+                jake,
+                "Welcome to Conduit",
+                "Everything is stored in memory",
+                "This backend lives in your browser tab. Reload the page to start over.",
+                &["welcome", "rust"],
+            );
+            db.insert_article(
+                demo,
+                "Building apps with Seed",
+                "Elm architecture in Rust",
+                "Model, Msg, update and view - that's it.",
+                &["seed", "rust"],
+            );
+            db.insert_comment(demo, welcome, "Nice to meet you, Jake!");
+        }
+        fake_conduit
+    }
+}
+
+// ------ Db ------
+
+#[derive(Default)]
+struct Db {
+    users: Vec<User>,
+    articles: Vec<ArticleRecord>,
+    comments: Vec<CommentRecord>,
+    /// (follower, followed)
+    follows: HashSet<(UserId, UserId)>,
+    favorites: HashSet<(UserId, ArticleId)>,
+    next_article_id: ArticleId,
+    next_comment_id: usize,
+}
+
+struct User {
+    id: UserId,
+    username: String,
+    email: String,
+    password: String,
+    bio: Option<String>,
+    image: Option<String>,
+    token: String,
+}
+
+struct ArticleRecord {
+    id: ArticleId,
+    author_id: UserId,
+    slug: String,
+    title: String,
+    description: String,
+    body: String,
+    tag_list: Vec<String>,
+    created_at: Timestamp,
+    updated_at: Timestamp,
+}
+
+struct CommentRecord {
+    id: usize,
+    article_id: ArticleId,
+    author_id: UserId,
+    body: String,
+    created_at: Timestamp,
+}
+
+impl Db {
+    // ------ inserts ------
+
+    fn insert_user(&mut self, username: &str, email: &str, password: &str) -> UserId {
+        let id = self.users.len();
+        self.users.push(User {
+            id,
+            username: username.into(),
+            email: email.into(),
+            password: password.into(),
+            bio: None,
+            image: None,
+            token: format!("fake-token-{}", id),
+        });
+        id
+    }
+
+    fn insert_article(
+        &mut self,
+        author_id: UserId,
+        title: &str,
+        description: &str,
+        body: &str,
+        tag_list: &[&str],
+    ) -> ArticleId {
+        let id = self.next_article_id;
+        self.next_article_id += 1;
+        let slug = self.unique_slug(title);
+        self.articles.push(ArticleRecord {
+            id,
+            author_id,
+            slug,
+            title: title.into(),
+            description: description.into(),
+            body: body.into(),
+            tag_list: tag_list.iter().map(|tag| (*tag).to_owned()).collect(),
+            created_at: now(),
+            updated_at: now(),
+        });
+        id
+    }
+
+    fn insert_comment(&mut self, author_id: UserId, article_id: ArticleId, body: &str) -> usize {
+        let id = self.next_comment_id;
+        self.next_comment_id += 1;
+        self.comments.push(CommentRecord {
+            id,
+            article_id,
+            author_id,
+            body: body.into(),
+            created_at: now(),
+        });
+        id
+    }
+
+    // ------ queries ------
+
+    /// Equivalent of the auth token check on the server.
+    fn current_user(&self, viewer: Option<&Viewer>) -> Result<UserId, ApiError> {
+        let viewer = viewer.ok_or(ApiError::Unauthorized)?;
+        self.users
+            .iter()
+            .find(|user| user.token == viewer.auth_token)
+            .map(|user| user.id)
+            .ok_or(ApiError::Unauthorized)
+    }
+
+    fn optional_user(&self, viewer: Option<&Viewer>) -> Option<UserId> {
+        self.current_user(viewer).ok()
+    }
+
+    fn user_by_username(&self, username: &str) -> Result<UserId, ApiError> {
+        self.users
+            .iter()
+            .find(|user| user.username == username)
+            .map(|user| user.id)
+            .ok_or(ApiError::NotFound)
+    }
+
+    fn article_by_slug(&self, slug: &str) -> Result<ArticleId, ApiError> {
+        self.articles
+            .iter()
+            .find(|article| article.slug == slug)
+            .map(|article| article.id)
+            .ok_or(ApiError::NotFound)
+    }
+
+    fn article_record(&self, article_id: ArticleId) -> &ArticleRecord {
+        self.articles
+            .iter()
+            .find(|article| article.id == article_id)
+            .expect("article record")
+    }
+
+    fn unique_slug(&self, title: &str) -> String {
+        let base_slug = title
+            .to_lowercase()
+            .split(|character: char| !character.is_alphanumeric())
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("-");
+        let mut slug = base_slug.clone();
+        let mut suffix = 1;
+        while self.articles.iter().any(|article| article.slug == slug) {
+            suffix += 1;
+            slug = format!("{}-{}", base_slug, suffix);
+        }
+        slug
+    }
+
+    // ------ entities ------
+
+    fn viewer(&self, user_id: UserId) -> Viewer {
+        let user = &self.users[user_id];
+        Viewer {
+            profile: self.profile(user_id),
+            auth_token: user.token.clone(),
+        }
+    }
+
+    fn profile(&self, user_id: UserId) -> Profile {
+        let user = &self.users[user_id];
+        Profile {
+            bio: user.bio.clone(),
+            avatar: Avatar::new(user.image.clone()),
+            username: user.username.clone().into(),
+        }
+    }
+
+    /// Same rules as `decoder::Author::into_author`.
+    fn author(&self, user_id: UserId, viewer: Option<&Viewer>) -> Author {
+        match self.optional_user(viewer) {
+            Some(viewer_id) if viewer_id == user_id => Author::IsViewer(self.viewer(user_id)),
+            Some(viewer_id) if self.follows.contains(&(viewer_id, user_id)) => {
+                Author::Following(self.profile(user_id))
+            }
+            _ => Author::NotFollowing(self.profile(user_id)),
+        }
+    }
+
+    fn article(&self, article_id: ArticleId, viewer: Option<&Viewer>) -> Article {
+        let record = self.article_record(article_id);
+        let viewer_id = self.optional_user(viewer);
+        Article {
+            title: record.title.clone(),
+            slug: record.slug.clone().into(),
+            body: record.body.clone().into(),
+            created_at: record.created_at.clone(),
+            updated_at: record.updated_at.clone(),
+            tag_list: record.tag_list.clone().into_tags(),
+            description: record.description.clone(),
+            author: self.author(record.author_id, viewer),
+            favorited: viewer_id
+                .is_some_and(|viewer_id| self.favorites.contains(&(viewer_id, article_id))),
+            favorites_count: self
+                .favorites
+                .iter()
+                .filter(|(_, favorited_id)| *favorited_id == article_id)
+                .count(),
+        }
+    }
+
+    fn comment(&self, record: &CommentRecord, viewer: Option<&Viewer>) -> Comment {
+        Comment {
+            id: record.id.into(),
+            body: record.body.clone(),
+            created_at: record.created_at.clone(),
+            updated_at: record.created_at.clone(),
+            author: self.author(record.author_id, viewer),
+        }
+    }
+
+    /// Newest articles first.
+    fn paginated_articles(
+        &self,
+        filter: impl Fn(&ArticleRecord) -> bool,
+        viewer: Option<&Viewer>,
+        per_page: NonZeroUsize,
+        page_number: PageNumber,
+    ) -> PaginatedList<Article> {
+        let article_ids = self
+            .articles
+            .iter()
+            .rev()
+            .filter(|article| filter(article))
+            .map(|article| article.id)
+            .collect::<Vec<_>>();
+
+        PaginatedList {
+            items: article_ids
+                .iter()
+                .skip((*page_number - 1) * per_page.get())
+                .take(per_page.get())
+                .map(|article_id| self.article(*article_id, viewer))
+                .collect(),
+            per_page,
+            total: article_ids.len(),
+        }
+    }
+
+    // ------ validation ------
+
+    fn check_username_is_free(
+        &self,
+        username: &str,
+        user_id: Option<UserId>,
+    ) -> Result<(), ApiError> {
+        if self
+            .users
+            .iter()
+            .any(|user| user.username == username && Some(user.id) != user_id)
+        {
+            Err(validation_error("username", "has already been taken"))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn check_article_author(&self, article_id: ArticleId, user_id: UserId) -> Result<(), ApiError> {
+        if self.article_record(article_id).author_id == user_id {
+            Ok(())
+        } else {
+            Err(ApiError::Forbidden)
+        }
+    }
+}
+
+impl ConduitApi for FakeConduit {
+    // ------ user ------
+
+    fn login(&self, valid_form: &login::ValidForm) -> ApiFuture<Viewer> {
+        let values = form_values(valid_form);
+        let db = self.db.borrow();
+        ready(
+            db.users
+                .iter()
+                .find(|user| user.email == values["email"] && user.password == values["password"])
+                .map(|user| db.viewer(user.id))
+                .ok_or_else(|| validation_error("email or password", "is invalid")),
+        )
+    }
+
+    fn register(&self, valid_form: &register::ValidForm) -> ApiFuture<Viewer> {
+        let values = form_values(valid_form);
+        let mut db = self.db.borrow_mut();
+        ready(
+            db.check_username_is_free(&values["username"], None)
+                .and_then(|_| {
+                    if db.users.iter().any(|user| user.email == values["email"]) {
+                        Err(validation_error("email", "has already been taken"))
+                    } else {
+                        Ok(())
+                    }
+                })
+                .map(|_| {
+                    let user_id =
+                        db.insert_user(&values["username"], &values["email"], &values["password"]);
+                    db.viewer(user_id)
+                }),
+        )
+    }
+
+    fn load_viewer(&self, viewer: &Viewer) -> ApiFuture<Viewer> {
+        let db = self.db.borrow();
+        ready(
+            db.current_user(Some(viewer))
+                .map(|user_id| db.viewer(user_id)),
+        )
+    }
+
+    fn load_settings(&self, viewer: Option<&Viewer>) -> ApiFuture<settings::Form> {
+        let db = self.db.borrow();
+        ready(db.current_user(viewer).map(|user_id| {
+            let user = &db.users[user_id];
+            settings::Form::new(vec![
+                settings::Field::Avatar(user.image.clone().unwrap_or_default()),
+                settings::Field::Username(user.username.clone()),
+                settings::Field::Bio(user.bio.clone().unwrap_or_default()),
+                settings::Field::Email(user.email.clone()),
+                settings::Field::Password(String::default()),
+            ])
+        }))
+    }
+
+    fn update_settings(
+        &self,
+        viewer: Option<&Viewer>,
+        valid_form: &settings::ValidForm,
+    ) -> ApiFuture<Viewer> {
+        let values = form_values(valid_form);
+        let mut db = self.db.borrow_mut();
+        ready(db.current_user(viewer).and_then(|user_id| {
+            db.check_username_is_free(&values["username"], Some(user_id))?;
+            let user = &mut db.users[user_id];
+            user.image = Some(values["image"].clone()).filter(|image| !image.is_empty());
+            user.username.clone_from(&values["username"]);
+            user.bio = Some(values["bio"].clone()).filter(|bio| !bio.is_empty());
+            user.email.clone_from(&values["email"]);
+            if !values["password"].is_empty() {
+                user.password.clone_from(&values["password"]);
+            }
+            Ok(db.viewer(user_id))
+        }))
+    }
+
+    // ------ profiles ------
+
+    fn load_author(
+        &self,
+        viewer: Option<Viewer>,
+        username: Username<'static>,
+    ) -> ApiFuture<Author, (Username<'static>, ApiError)> {
+        let db = self.db.borrow();
+        ready(
+            db.user_by_username(username.as_str())
+                .map(|user_id| db.author(user_id, viewer.as_ref()))
+                .map_err(|error| (username, error)),
+        )
+    }
+
+    fn follow(&self, viewer: Option<Viewer>, username: &Username) -> ApiFuture<Author> {
+        self.change_follow(viewer, username, true)
+    }
+
+    fn unfollow(&self, viewer: Option<Viewer>, username: &Username) -> ApiFuture<Author> {
+        self.change_follow(viewer, username, false)
+    }
+
+    // ------ articles ------
+
+    fn load_article(&self, viewer: Option<Viewer>, slug: &Slug) -> ApiFuture<Article> {
+        let db = self.db.borrow();
+        ready(
+            db.article_by_slug(slug.as_str())
+                .map(|article_id| db.article(article_id, viewer.as_ref())),
+        )
+    }
+
+    fn load_article_for_editor(
+        &self,
+        viewer: Option<Viewer>,
+        slug: Slug,
+    ) -> ApiFuture<Article, (Slug, ApiError)> {
+        let db = self.db.borrow();
+        ready(
+            db.article_by_slug(slug.as_str())
+                .map(|article_id| db.article(article_id, viewer.as_ref()))
+                .map_err(|error| (slug, error)),
+        )
+    }
+
+    fn create_article(
+        &self,
+        viewer: Option<Viewer>,
+        valid_form: &article_editor::ValidForm,
+    ) -> ApiFuture<Article> {
+        let values = form_values(valid_form);
+        let mut db = self.db.borrow_mut();
+        ready(db.current_user(viewer.as_ref()).map(|user_id| {
+            let article_id = db.insert_article(
+                user_id,
+                &values["title"],
+                &values["description"],
+                &values["body"],
+                &split_tags(&values["tags"]),
+            );
+            db.article(article_id, viewer.as_ref())
+        }))
+    }
+
+    fn update_article(
+        &self,
+        viewer: Option<Viewer>,
+        valid_form: &article_editor::ValidForm,
+        slug: &Slug,
+    ) -> ApiFuture<Article> {
+        let values = form_values(valid_form);
+        let mut db = self.db.borrow_mut();
+        ready((|| {
+            let user_id = db.current_user(viewer.as_ref())?;
+            let article_id = db.article_by_slug(slug.as_str())?;
+            db.check_article_author(article_id, user_id)?;
+
+            let new_slug = if db.article_record(article_id).title == values["title"] {
+                slug.to_string()
+            } else {
+                db.unique_slug(&values["title"])
+            };
+            let record = db
+                .articles
+                .iter_mut()
+                .find(|article| article.id == article_id)
+                .expect("article record");
+            record.slug = new_slug;
+            record.title.clone_from(&values["title"]);
+            record.description.clone_from(&values["description"]);
+            record.body.clone_from(&values["body"]);
+            record.tag_list = split_tags(&values["tags"])
+                .into_iter()
+                .map(ToOwned::to_owned)
+                .collect();
+            record.updated_at = now();
+            Ok(db.article(article_id, viewer.as_ref()))
+        })())
+    }
+
+    fn delete_article(&self, viewer: Option<&Viewer>, slug: &Slug) -> ApiFuture<()> {
+        let mut db = self.db.borrow_mut();
+        ready((|| {
+            let user_id = db.current_user(viewer)?;
+            let article_id = db.article_by_slug(slug.as_str())?;
+            db.check_article_author(article_id, user_id)?;
+
+            db.articles.retain(|article| article.id != article_id);
+            db.comments
+                .retain(|comment| comment.article_id != article_id);
+            db.favorites
+                .retain(|(_, favorited_id)| *favorited_id != article_id);
+            Ok(())
+        })())
+    }
+
+    fn favorite(&self, viewer: Option<Viewer>, slug: &Slug) -> ApiFuture<Article> {
+        self.change_favorite(viewer, slug, true)
+    }
+
+    fn unfavorite(&self, viewer: Option<Viewer>, slug: &Slug) -> ApiFuture<Article> {
+        self.change_favorite(viewer, slug, false)
+    }
+
+    // ------ feeds ------
+
+    fn load_home_feed(
+        &self,
+        viewer: Option<Viewer>,
+        selected_feed: &page::home::SelectedFeed,
+        page_number: PageNumber,
+        per_page: NonZeroUsize,
+    ) -> ApiFuture<PaginatedList<Article>> {
+        use page::home::SelectedFeed;
+
+        let db = self.db.borrow();
+        ready(match selected_feed {
+            SelectedFeed::Your(your_viewer) => db.current_user(Some(your_viewer)).map(|user_id| {
+                db.paginated_articles(
+                    |article| db.follows.contains(&(user_id, article.author_id)),
+                    viewer.as_ref(),
+                    per_page,
+                    page_number,
+                )
+            }),
+            SelectedFeed::Global => {
+                Ok(db.paginated_articles(|_| true, viewer.as_ref(), per_page, page_number))
+            }
+            SelectedFeed::Tag(tag) => {
+                let tag = tag.to_string();
+                Ok(db.paginated_articles(
+                    |article| article.tag_list.contains(&tag),
+                    viewer.as_ref(),
+                    per_page,
+                    page_number,
+                ))
+            }
+        })
+    }
+
+    fn load_profile_feed(
+        &self,
+        viewer: Option<Viewer>,
+        username: Username<'static>,
+        selected_feed: page::profile::SelectedFeed,
+        page_number: PageNumber,
+        per_page: NonZeroUsize,
+    ) -> ApiFuture<PaginatedList<Article>, (Username<'static>, ApiError)> {
+        use page::profile::SelectedFeed;
+
+        let db = self.db.borrow();
+        // Unknown usernames lead to empty lists, like on the real server.
+        let user_id = db.user_by_username(username.as_str()).ok();
+        ready(Ok(match selected_feed {
+            SelectedFeed::MyArticles => db.paginated_articles(
+                |article| Some(article.author_id) == user_id,
+                viewer.as_ref(),
+                per_page,
+                page_number,
+            ),
+            SelectedFeed::FavoritedArticles => db.paginated_articles(
+                |article| {
+                    user_id.is_some_and(|user_id| db.favorites.contains(&(user_id, article.id)))
+                },
+                viewer.as_ref(),
+                per_page,
+                page_number,
+            ),
+        }))
+    }
+
+    fn load_tags(&self) -> ApiFuture<Vec<Tag>> {
+        let db = self.db.borrow();
+        let mut tags = Vec::new();
+        for tag in db.articles.iter().flat_map(|article| &article.tag_list) {
+            if !tags.contains(tag) {
+                tags.push(tag.clone());
+            }
+        }
+        ready(Ok(tags.into_tags()))
+    }
+
+    // ------ comments ------
+
+    fn load_comments(&self, viewer: Option<Viewer>, slug: &Slug) -> ApiFuture<VecDeque<Comment>> {
+        let db = self.db.borrow();
+        ready(db.article_by_slug(slug.as_str()).map(|article_id| {
+            db.comments
+                .iter()
+                .rev()
+                .filter(|comment| comment.article_id == article_id)
+                .map(|comment| db.comment(comment, viewer.as_ref()))
+                .collect()
+        }))
+    }
+
+    fn create_comment(
+        &self,
+        viewer: Option<Viewer>,
+        slug: &Slug,
+        text: String,
+    ) -> ApiFuture<Comment> {
+        let mut db = self.db.borrow_mut();
+        ready((|| {
+            let user_id = db.current_user(viewer.as_ref())?;
+            let article_id = db.article_by_slug(slug.as_str())?;
+            let comment_id = db.insert_comment(user_id, article_id, &text);
+            let record = db
+                .comments
+                .iter()
+                .find(|comment| comment.id == comment_id)
+                .expect("comment record");
+            Ok(db.comment(record, viewer.as_ref()))
+        })())
+    }
+
+    fn delete_comment(
+        &self,
+        viewer: Option<&Viewer>,
+        slug: &Slug,
+        comment_id: CommentId,
+    ) -> ApiFuture<CommentId> {
+        let mut db = self.db.borrow_mut();
+        ready((|| {
+            let user_id = db.current_user(viewer)?;
+            let article_id = db.article_by_slug(slug.as_str())?;
+            let record = db
+                .comments
+                .iter()
+                .find(|comment| {
+                    comment.article_id == article_id && CommentId::from(comment.id) == comment_id
+                })
+                .ok_or(ApiError::NotFound)?;
+            if record.author_id != user_id {
+                return Err(ApiError::Forbidden);
+            }
+            let record_id = record.id;
+            db.comments.retain(|comment| comment.id != record_id);
+            Ok(comment_id)
+        })())
+    }
+}
+
+impl FakeConduit {
+    fn change_follow(
+        &self,
+        viewer: Option<Viewer>,
+        username: &Username,
+        follow: bool,
+    ) -> ApiFuture<Author> {
+        let mut db = self.db.borrow_mut();
+        ready((|| {
+            let viewer_id = db.current_user(viewer.as_ref())?;
+            let user_id = db.user_by_username(username.as_str())?;
+            if follow {
+                db.follows.insert((viewer_id, user_id));
+            } else {
+                db.follows.remove(&(viewer_id, user_id));
+            }
+            Ok(db.author(user_id, viewer.as_ref()))
+        })())
+    }
+
+    fn change_favorite(
+        &self,
+        viewer: Option<Viewer>,
+        slug: &Slug,
+        favorite: bool,
+    ) -> ApiFuture<Article> {
+        let mut db = self.db.borrow_mut();
+        ready((|| {
+            let user_id = db.current_user(viewer.as_ref())?;
+            let article_id = db.article_by_slug(slug.as_str())?;
+            if favorite {
+                db.favorites.insert((user_id, article_id));
+            } else {
+                db.favorites.remove(&(user_id, article_id));
+            }
+            Ok(db.article(article_id, viewer.as_ref()))
+        })())
+    }
+}
+
+// ====== PRIVATE ======
+
+fn ready<T: 'static, E: 'static>(result: Result<T, E>) -> ApiFuture<T, E> {
+    Box::pin(future::ready(result))
+}
+
+fn now() -> Timestamp {
+    Local::now().into()
+}
+
+fn form_values<T: FormField>(valid_form: &form::ValidForm<T>) -> HashMap<&'static str, String> {
+    valid_form
+        .iter_keys_and_fields()
+        .map(|(key, field)| (*key, field.value().to_owned()))
+        .collect()
+}
+
+fn split_tags(tags: &str) -> Vec<&str> {
+    tags.split(' ').filter(|tag| !tag.is_empty()).collect()
+}
+
+fn validation_error(field: &str, message: &str) -> ApiError {
+    let mut errors = IndexMap::new();
+    errors.insert(field.to_owned(), vec![message.to_owned()]);
+    ApiError::Validation(errors)
+}
+
+// ====== ====== TESTS ====== ======
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{api, request, storage};
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn login_form(email: &str, password: &str) -> login::ValidForm {
+        login::Form::new(vec![
+            login::Field::Email(email.into()),
+            login::Field::Password(password.into()),
+        ])
+        .trim_fields()
+        .validate()
+        .unwrap_or_else(|_| panic!("invalid login form"))
+    }
+
+    pub(crate) async fn log_in_as_demo(fake_conduit: &FakeConduit) -> Viewer {
+        fake_conduit
+            .login(&login_form("demo@example.com", "password"))
+            .await
+            .unwrap_or_else(|error| panic!("login failed: {:?}", error))
+    }
+
+    #[wasm_bindgen_test]
+    async fn login_with_wrong_password_test() {
+        // ====== ARRANGE ======
+        let fake_conduit = FakeConduit::with_demo_data();
+
+        // ====== ACT ======
+        let result = fake_conduit
+            .login(&login_form("demo@example.com", "wrong password"))
+            .await;
+
+        // ====== ASSERT ======
+        assert!(matches!(result, Err(ApiError::Validation(_))));
+    }
+
+    #[wasm_bindgen_test]
+    async fn load_viewer_with_unknown_token_test() {
+        // ====== ARRANGE ======
+        let fake_conduit = FakeConduit::with_demo_data();
+        let mut viewer = log_in_as_demo(&fake_conduit).await;
+        viewer.auth_token = "revoked token".into();
+
+        // ====== ACT ======
+        let result = fake_conduit.load_viewer(&viewer).await;
+
+        // ====== ASSERT ======
+        assert!(matches!(result, Err(ApiError::Unauthorized)));
+    }
+
+    #[wasm_bindgen_test]
+    async fn cmd_reports_unknown_token_test() {
+        // ====== ARRANGE ======
+        storage::tests::use_in_memory_backend();
+        let fake_conduit = FakeConduit::with_demo_data();
+        let mut viewer = log_in_as_demo(&fake_conduit).await;
+        viewer.auth_token = "revoked token".into();
+        storage::store_viewer(&viewer);
+        let rejected_token = Rc::new(RefCell::new(None));
+        let handler_rejected_token = Rc::clone(&rejected_token);
+        api::set_unauthorized_handler(move |auth_token| {
+            handler_rejected_token.replace(Some(auth_token));
+        });
+
+        // ====== ACT ======
+        let msg = api::cmd(fake_conduit.load_viewer(&viewer), |result| result.is_ok()).await;
+
+        // ====== ASSERT ======
+        assert_eq!(msg, Ok(false));
+        assert_eq!(*rejected_token.borrow(), Some("revoked token".to_owned()));
+    }
+
+    #[wasm_bindgen_test]
+    async fn favorite_test() {
+        // ====== ARRANGE ======
+        let fake_conduit = FakeConduit::with_demo_data();
+        let viewer = log_in_as_demo(&fake_conduit).await;
+        let slug = Slug::from("welcome-to-conduit".to_owned());
+
+        // ====== ACT ======
+        let article = fake_conduit
+            .favorite(Some(viewer), &slug)
+            .await
+            .unwrap_or_else(|error| panic!("favorite failed: {:?}", error));
+
+        // ====== ASSERT ======
+        assert!(article.favorited);
+        assert_eq!(article.favorites_count, 1);
+    }
+
+    #[wasm_bindgen_test]
+    async fn your_feed_contains_followed_authors_test() {
+        // ====== ARRANGE ======
+        let fake_conduit = FakeConduit::with_demo_data();
+        let viewer = log_in_as_demo(&fake_conduit).await;
+        let selected_feed = page::home::SelectedFeed::Your(viewer.clone());
+
+        // ====== ACT ======
+        let feed_before_follow = fake_conduit
+            .load_home_feed(
+                Some(viewer.clone()),
+                &selected_feed,
+                PageNumber::default(),
+                *request::feed::HOME_ARTICLES_PER_PAGE,
+            )
+            .await
+            .unwrap_or_else(|error| panic!("load feed failed: {:?}", error));
+        fake_conduit
+            .follow(Some(viewer.clone()), &Username::from("jake"))
+            .await
+            .unwrap_or_else(|error| panic!("follow failed: {:?}", error));
+        let feed_after_follow = fake_conduit
+            .load_home_feed(
+                Some(viewer),
+                &selected_feed,
+                PageNumber::default(),
+                *request::feed::HOME_ARTICLES_PER_PAGE,
+            )
+            .await
+            .unwrap_or_else(|error| panic!("load feed failed: {:?}", error));
+
+        // ====== ASSERT ======
+        assert_eq!(feed_before_follow.total, 0);
+        assert_eq!(feed_after_follow.total, 1);
+        assert_eq!(feed_after_follow.items[0].title, "Welcome to Conduit");
+    }
+
+    #[wasm_bindgen_test]
+    async fn delete_article_of_other_author_test() {
+        // ====== ARRANGE ======
+        let fake_conduit = FakeConduit::with_demo_data();
+        let viewer = log_in_as_demo(&fake_conduit).await;
+        let slug = Slug::from("welcome-to-conduit".to_owned());
+
+        // ====== ACT ======
+        let result = fake_conduit.delete_article(Some(&viewer), &slug).await;
+
+        // ====== ASSERT ======
+        assert!(matches!(result, Err(ApiError::Forbidden)));
+    }
+}
@@ -0,0 +1,177 @@
+use crate::{
+    api::{ApiFuture, ConduitApi},
+    entity::{
+        form::{article_editor, login, register, settings},
+        ApiError, Article, Author, Comment, CommentId, PageNumber, PaginatedList, Slug, Tag,
+        Username, Viewer,
+    },
+    page, request,
+};
+use std::{collections::VecDeque, convert::identity, future::Future, num::NonZeroUsize};
+
+// ------ HttpConduit ------
+
+/// Calls the Conduit server at `request::base_url`.
+pub struct HttpConduit;
+
+impl ConduitApi for HttpConduit {
+    // ------ user ------
+
+    fn login(&self, valid_form: &login::ValidForm) -> ApiFuture<Viewer> {
+        boxed(request::login::login(valid_form, identity))
+    }
+
+    fn register(&self, valid_form: &register::ValidForm) -> ApiFuture<Viewer> {
+        boxed(request::register::register(valid_form, identity))
+    }
+
+    fn load_viewer(&self, viewer: &Viewer) -> ApiFuture<Viewer> {
+        boxed(request::viewer::load(viewer, identity))
+    }
+
+    fn load_settings(&self, viewer: Option<&Viewer>) -> ApiFuture<settings::Form> {
+        boxed(request::settings::load(viewer, identity))
+    }
+
+    fn update_settings(
+        &self,
+        viewer: Option<&Viewer>,
+        valid_form: &settings::ValidForm,
+    ) -> ApiFuture<Viewer> {
+        boxed(request::settings::update(viewer, valid_form, identity))
+    }
+
+    // ------ profiles ------
+
+    fn load_author(
+        &self,
+        viewer: Option<Viewer>,
+        username: Username<'static>,
+    ) -> ApiFuture<Author, (Username<'static>, ApiError)> {
+        boxed(request::author::load(viewer, username, identity))
+    }
+
+    fn follow(&self, viewer: Option<Viewer>, username: &Username) -> ApiFuture<Author> {
+        boxed(request::follow::follow(viewer, username, identity))
+    }
+
+    fn unfollow(&self, viewer: Option<Viewer>, username: &Username) -> ApiFuture<Author> {
+        boxed(request::follow::unfollow(viewer, username, identity))
+    }
+
+    // ------ articles ------
+
+    fn load_article(&self, viewer: Option<Viewer>, slug: &Slug) -> ApiFuture<Article> {
+        boxed(request::article::load(viewer, slug, identity))
+    }
+
+    fn load_article_for_editor(
+        &self,
+        viewer: Option<Viewer>,
+        slug: Slug,
+    ) -> ApiFuture<Article, (Slug, ApiError)> {
+        boxed(request::article::load_for_editor(viewer, slug, identity))
+    }
+
+    fn create_article(
+        &self,
+        viewer: Option<Viewer>,
+        valid_form: &article_editor::ValidForm,
+    ) -> ApiFuture<Article> {
+        boxed(request::article::create(viewer, valid_form, identity))
+    }
+
+    fn update_article(
+        &self,
+        viewer: Option<Viewer>,
+        valid_form: &article_editor::ValidForm,
+        slug: &Slug,
+    ) -> ApiFuture<Article> {
+        boxed(request::article::update(viewer, valid_form, slug, identity))
+    }
+
+    fn delete_article(&self, viewer: Option<&Viewer>, slug: &Slug) -> ApiFuture<()> {
+        boxed(request::article::delete(viewer, slug, identity))
+    }
+
+    fn favorite(&self, viewer: Option<Viewer>, slug: &Slug) -> ApiFuture<Article> {
+        boxed(request::favorite::favorite(viewer, slug, identity))
+    }
+
+    fn unfavorite(&self, viewer: Option<Viewer>, slug: &Slug) -> ApiFuture<Article> {
+        boxed(request::favorite::unfavorite(viewer, slug, identity))
+    }
+
+    // ------ feeds ------
+
+    fn load_home_feed(
+        &self,
+        viewer: Option<Viewer>,
+        selected_feed: &page::home::SelectedFeed,
+        page_number: PageNumber,
+        per_page: NonZeroUsize,
+    ) -> ApiFuture<PaginatedList<Article>> {
+        boxed(request::feed::load_for_home(
+            viewer,
+            selected_feed,
+            page_number,
+            per_page,
+            identity,
+        ))
+    }
+
+    fn load_profile_feed(
+        &self,
+        viewer: Option<Viewer>,
+        username: Username<'static>,
+        selected_feed: page::profile::SelectedFeed,
+        page_number: PageNumber,
+        per_page: NonZeroUsize,
+    ) -> ApiFuture<PaginatedList<Article>, (Username<'static>, ApiError)> {
+        boxed(request::feed::load_for_profile(
+            viewer,
+            username,
+            selected_feed,
+            page_number,
+            per_page,
+            identity,
+        ))
+    }
+
+    fn load_tags(&self) -> ApiFuture<Vec<Tag>> {
+        boxed(request::tag::load_list(identity))
+    }
+
+    // ------ comments ------
+
+    fn load_comments(&self, viewer: Option<Viewer>, slug: &Slug) -> ApiFuture<VecDeque<Comment>> {
+        boxed(request::comment::load_list(viewer, slug, identity))
+    }
+
+    fn create_comment(
+        &self,
+        viewer: Option<Viewer>,
+        slug: &Slug,
+        text: String,
+    ) -> ApiFuture<Comment> {
+        boxed(request::comment::create(viewer, slug, text, identity))
+    }
+
+    fn delete_comment(
+        &self,
+        viewer: Option<&Viewer>,
+        slug: &Slug,
+        comment_id: CommentId,
+    ) -> ApiFuture<CommentId> {
+        boxed(request::comment::delete(viewer, slug, comment_id, identity))
+    }
+}
+
+// ====== PRIVATE ======
+
+/// Request functions pass their result to the callback (`identity` here) and always resolve to `Ok`.
+fn boxed<T, E>(
+    request: impl Future<Output = Result<Result<T, E>, Result<T, E>>> + 'static,
+) -> ApiFuture<T, E> {
+    Box::pin(async { request.await.unwrap_or_else(identity) })
+}
@@ -1,4 +1,3 @@
-use crate::entity;
 use indexmap::IndexMap;
 use serde::Deserialize;
 
@@ -9,11 +8,9 @@ pub struct ErrorMessages {
 }
 
 impl ErrorMessages {
-    pub fn into_error_messages(self) -> Vec<entity::ErrorMessage> {
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn into_field_errors(self) -> IndexMap<String, Vec<String>> {
         self.errors
-            .into_iter()
-            .map(|(field, errors)| format!("{} {}", field, errors.join(", ")).into())
-            .collect()
     }
 }
 
@@ -0,0 +1,82 @@
+use crate::entity::{form::Problem, ErrorMessage};
+use indexmap::IndexMap;
+
+// ------ ApiError ------
+
+#[derive(Clone, Debug)]
+pub enum ApiError {
+    /// Request didn't reach the server or the response didn't reach us.
+    Network,
+    Timeout,
+    /// Status 401 - the auth token is missing, expired or revoked.
+    Unauthorized,
+    /// Status 403.
+    Forbidden,
+    /// Status 404.
+    NotFound,
+    /// Server rejected sent data - messages grouped by field key (e.g. "email" => ["is invalid"]).
+    Validation(IndexMap<String, Vec<String>>),
+    /// Other unsuccessful statuses.
+    Server(u16),
+    /// Response body or its content cannot be decoded.
+    Decode(ErrorMessage),
+}
+
+impl ApiError {
+    pub fn into_error_messages(self) -> Vec<ErrorMessage> {
+        use ApiError::*;
+        match self {
+            Network => vec!["Network error".into()],
+            Timeout => vec!["Request timed out".into()],
+            Unauthorized => vec!["You have to sign in".into()],
+            Forbidden => vec!["You are not allowed to do that".into()],
+            NotFound => vec!["Not found".into()],
+            Validation(errors) => errors
+                .into_iter()
+                .map(|(field, messages)| format!("{} {}", field, messages.join(", ")).into())
+                .collect(),
+            Server(status_code) => vec![format!("Server error ({})", status_code).into()],
+            Decode(error) => vec![error],
+        }
+    }
+
+    pub fn into_problems(self) -> Vec<Problem> {
+        self.into_error_messages()
+            .into_iter()
+            .map(|error| Problem::new_server_error(error.into_inner()))
+            .collect()
+    }
+}
+
+// ====== ====== TESTS ====== ======
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn validation_into_error_messages_test() {
+        // ====== ARRANGE ======
+        let mut errors = IndexMap::new();
+        errors.insert(
+            "email".to_owned(),
+            vec!["is invalid".to_owned(), "is too long".to_owned()],
+        );
+        errors.insert("password".to_owned(), vec!["is too short".to_owned()]);
+
+        // ====== ACT ======
+        let messages = ApiError::Validation(errors).into_error_messages();
+
+        // ====== ASSERT ======
+        assert_eq!(
+            messages
+                .iter()
+                .map(|message| message.to_string())
+                .collect::<Vec<_>>(),
+            vec!["email is invalid, is too long", "password is too short"]
+        );
+    }
+}
@@ -1,8 +1,10 @@
 use crate::{
+    api,
     entity::{
-        author, timestamp, Article, ErrorMessage, PageNumber, PaginatedList, Slug, Tag, Viewer,
+        author, timestamp, ApiError, Article, ErrorMessage, PageNumber, PaginatedList,
+        PendingMutation, Slug, Tag, Viewer,
     },
-    logger, page, request, GMsg, Route, Session,
+    logger, offline, page, GMsg, Route, Session,
 };
 use seed::prelude::*;
 use std::borrow::Cow;
@@ -11,25 +13,65 @@ use std::borrow::Cow;
 //     Model
 // ------ ------
 
+// ------ RequestId ------
+
+/// Identifies a feed request.
+/// Only the response to the latest request is applied - the other ones are stale.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RequestId(u32);
+
+impl RequestId {
+    #[must_use]
+    pub const fn next(self) -> Self {
+        Self(self.0.wrapping_add(1))
+    }
+}
+
+// ------ Model ------
+
 #[derive(Default)]
 pub struct Model {
     session: Session,
     errors: Vec<ErrorMessage>,
     articles: PaginatedList<Article>,
+    /// Articles with favorite changes waiting for the connection.
+    pending_slugs: Vec<Slug>,
+}
+
+impl Model {
+    pub fn pending_mutations_replayed(&mut self, report: &offline::ReplayReport) {
+        self.pending_slugs = apply_pending_favorites(&mut self.articles.items);
+        self.errors.extend(report.conflict_messages());
+    }
 }
 
 // ------ ------
 //     Init
 // ------ ------
 
-pub fn init(session: Session, articles: PaginatedList<Article>) -> Model {
+pub fn init(session: Session, mut articles: PaginatedList<Article>) -> Model {
+    let pending_slugs = apply_pending_favorites(&mut articles.items);
     Model {
         session,
         articles,
+        pending_slugs,
         ..Model::default()
     }
 }
 
+/// Show favorite changes made offline; returns slugs of the changed articles.
+fn apply_pending_favorites(articles: &mut [Article]) -> Vec<Slug> {
+    articles
+        .iter_mut()
+        .filter_map(|article| {
+            offline::pending_favorite(&article.slug).map(|favorited| {
+                article.set_favorited(favorited);
+                article.slug.clone()
+            })
+        })
+        .collect()
+}
+
 // ------ ------
 //    Update
 // ------ ------
@@ -39,7 +81,7 @@ pub enum Msg {
     DismissErrorsClicked,
     FavoriteClicked(Slug),
     UnfavoriteClicked(Slug),
-    FavoriteCompleted(Result<Article, Vec<ErrorMessage>>),
+    FavoriteCompleted(Result<Article, ApiError>),
 }
 
 #[allow(clippy::option_map_unit_fn, clippy::find_map)]
@@ -48,20 +90,30 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>)
         Msg::DismissErrorsClicked => {
             model.errors.clear();
         }
+        Msg::FavoriteClicked(slug) if !offline::is_online() => {
+            queue_favorite_change(model, slug, false);
+        }
+        Msg::UnfavoriteClicked(slug) if !offline::is_online() => {
+            queue_favorite_change(model, slug, true);
+        }
         Msg::FavoriteClicked(slug) => {
             orders
-                .perform_cmd(request::favorite::unfavorite(
-                    model.session.viewer().cloned(),
-                    &slug,
+                .perform_cmd(api::cmd(
+                    model
+                        .session
+                        .api()
+                        .unfavorite(model.session.viewer().cloned(), &slug),
                     Msg::FavoriteCompleted,
                 ))
                 .skip();
         }
         Msg::UnfavoriteClicked(slug) => {
             orders
-                .perform_cmd(request::favorite::favorite(
-                    model.session.viewer().cloned(),
-                    &slug,
+                .perform_cmd(api::cmd(
+                    model
+                        .session
+                        .api()
+                        .favorite(model.session.viewer().cloned(), &slug),
                     Msg::FavoriteCompleted,
                 ))
                 .skip();
@@ -74,13 +126,36 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>)
                 .find(|old_article| old_article.slug == article.slug)
                 .map(|old_article| *old_article = article);
         }
-        Msg::FavoriteCompleted(Err(errors)) => {
-            logger::errors(&errors);
-            model.errors = errors;
+        Msg::FavoriteCompleted(Err(error)) => {
+            logger::error(&error);
+            model.errors = error.into_error_messages();
         }
     }
 }
 
+fn queue_favorite_change(model: &mut Model, slug: Slug, favorited: bool) {
+    offline::enqueue(if favorited {
+        PendingMutation::Favorite {
+            slug: slug.to_string(),
+        }
+    } else {
+        PendingMutation::Unfavorite {
+            slug: slug.to_string(),
+        }
+    });
+    if let Some(article) = model
+        .articles
+        .items
+        .iter_mut()
+        .find(|article| article.slug == slug)
+    {
+        article.set_favorited(favorited);
+    }
+    if !model.pending_slugs.contains(&slug) {
+        model.pending_slugs.push(slug);
+    }
+}
+
 // ------ ------
 //     View
 // ------ ------
@@ -129,7 +204,10 @@ pub fn view_articles(model: &Model) -> Vec<Node<Msg>> {
                 .articles
                 .items
                 .iter()
-                .map(|article| view_article_preview(model.session.viewer(), article))
+                .map(|article| {
+                    let pending = model.pending_slugs.contains(&article.slug);
+                    view_article_preview(&model.session, article, pending)
+                })
                 .collect()
         })
         .collect()
@@ -174,7 +252,7 @@ fn view_tab<Ms: Clone>(tab: Tab<Ms>) -> Node<Ms> {
 
 // ------ view_articles helpers ------
 
-fn view_article_preview(viewer: Option<&Viewer>, article: &Article) -> Node<Msg> {
+fn view_article_preview(session: &Session, article: &Article, pending: bool) -> Node<Msg> {
     div![
         class!["article-preview"],
         div![
@@ -186,9 +264,14 @@ fn view_article_preview(viewer: Option<&Viewer>, article: &Article) -> Node<Msg>
             div![
                 class!["info"],
                 author::view(article.author.username()),
-                timestamp::view(&article.created_at)
+                timestamp::view(&article.created_at, session.preferences().date_format),
+                if pending {
+                    offline::view_pending_badge()
+                } else {
+                    empty![]
+                }
             ],
-            view_favorite_button(viewer, article)
+            view_favorite_button(session.viewer(), article)
         ],
         a![
             class!["preview-link"],
@@ -242,3 +325,64 @@ fn view_page_link<Ms: Clone>(page_number: PageNumber, active: bool, msg: Ms) ->
         ]
     ]
 }
+
+// ====== ====== TESTS ====== ======
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        api::{fake::tests::log_in_as_demo, ConduitApi, FakeConduit},
+        helper::tests::TestOrders,
+        storage,
+    };
+    use std::rc::Rc;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn request_id_next_test() {
+        // ====== ARRANGE ======
+        let request_id = RequestId::default();
+
+        // ====== ACT ======
+        let next_request_id = request_id.next();
+
+        // ====== ASSERT ======
+        assert_ne!(request_id, next_request_id);
+        assert_eq!(next_request_id, RequestId::default().next());
+    }
+
+    #[wasm_bindgen_test]
+    async fn favorite_with_fake_conduit_test() {
+        // ====== ARRANGE ======
+        storage::tests::use_in_memory_backend();
+        let fake_conduit = Rc::new(FakeConduit::with_demo_data());
+        let viewer = log_in_as_demo(&fake_conduit).await;
+        let slug = Slug::from("welcome-to-conduit".to_owned());
+        let article = fake_conduit
+            .load_article(None, &slug)
+            .await
+            .unwrap_or_else(|error| panic!("article not loaded: {:?}", error));
+        let session = Session::new(Some(viewer), fake_conduit);
+        let mut model = init(
+            session,
+            PaginatedList {
+                items: vec![article],
+                ..PaginatedList::default()
+            },
+        );
+        let mut orders = TestOrders::default();
+
+        // ====== ACT ======
+        update(Msg::UnfavoriteClicked(slug), &mut model, &mut orders);
+        for msg in orders.run_cmds().await {
+            update(msg, &mut model, &mut orders);
+        }
+
+        // ====== ASSERT ======
+        assert!(model.errors.is_empty());
+        assert!(model.articles.items[0].favorited);
+    }
+}
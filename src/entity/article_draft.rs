@@ -0,0 +1,181 @@
+use crate::entity::{
+    form::article_editor::{Field, Form},
+    Slug, Timestamp,
+};
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// ------ DraftTarget ------
+
+/// Article which the draft will be published as.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum DraftTarget {
+    /// A new article - `id` distinguishes drafts of different new articles.
+    New {
+        id: u64,
+    },
+    Edit {
+        slug: String,
+    },
+}
+
+impl DraftTarget {
+    /// Target for a new article which hasn't been drafted yet.
+    pub fn new_article() -> Self {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let id = js_sys::Date::now() as u64;
+        Self::New { id }
+    }
+
+    pub fn edit(slug: &Slug) -> Self {
+        Self::Edit {
+            slug: slug.as_str().to_owned(),
+        }
+    }
+
+    pub fn is_new_article(&self, draft_id: &str) -> bool {
+        matches!(self, Self::New { id } if id.to_string() == draft_id)
+    }
+}
+
+// ------ ArticleDraft ------
+
+/// Unsaved content of the article editor.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct ArticleDraft {
+    pub target: DraftTarget,
+    pub title: String,
+    pub description: String,
+    pub body: String,
+    pub tags: String,
+    /// Milliseconds since the Unix epoch.
+    pub edited_at_ms: f64,
+}
+
+impl ArticleDraft {
+    pub fn new(target: DraftTarget, form: &Form, edited_at_ms: f64) -> Self {
+        let mut draft = Self {
+            target,
+            title: String::new(),
+            description: String::new(),
+            body: String::new(),
+            tags: String::new(),
+            edited_at_ms,
+        };
+        for field in form.iter_fields() {
+            match field {
+                Field::Title(value) => draft.title.clone_from(value),
+                Field::Description(value) => draft.description.clone_from(value),
+                Field::Body(value) => draft.body.clone_from(value),
+                Field::Tags(value) => draft.tags.clone_from(value),
+            }
+        }
+        draft
+    }
+
+    pub fn to_form(&self) -> Form {
+        Form::new(vec![
+            Field::Title(self.title.clone()),
+            Field::Description(self.description.clone()),
+            Field::Body(self.body.clone()),
+            Field::Tags(self.tags.clone()),
+        ])
+    }
+
+    /// All fields are blank - there's nothing to save.
+    pub fn is_empty(&self) -> bool {
+        [&self.title, &self.description, &self.body, &self.tags]
+            .iter()
+            .all(|value| value.trim().is_empty())
+    }
+
+    /// The draft in a Markdown file - the title as the heading, tags at the end.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!("# {}\n\n", self.title.trim());
+        if !self.description.trim().is_empty() {
+            markdown.push_str(&format!("> {}\n\n", self.description.trim()));
+        }
+        markdown.push_str(self.body.trim());
+        markdown.push('\n');
+        if !self.tags.trim().is_empty() {
+            markdown.push_str(&format!("\nTags: {}\n", self.tags.trim()));
+        }
+        markdown
+    }
+
+    /// E.g. `how-to-train-your-dragon.md`.
+    pub fn file_name(&self) -> String {
+        let name = self
+            .title
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect::<Vec<_>>()
+            .join("-");
+        if name.is_empty() {
+            "draft.md".to_owned()
+        } else {
+            format!("{}.md", name)
+        }
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn edited_at(&self) -> Option<Timestamp> {
+        Local
+            .timestamp_millis_opt(self.edited_at_ms as i64)
+            .single()
+            .map(Timestamp::from)
+    }
+}
+
+// ====== ====== TESTS ====== ======
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::entity::form::FormField;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn form_round_trip_test() {
+        // ====== ARRANGE ======
+        let mut form = Form::default();
+        form.upsert_field(Field::Title("I'm title".into()));
+        form.upsert_field(Field::Tags("rust seed".into()));
+
+        // ====== ACT ======
+        let draft = ArticleDraft::new(DraftTarget::New { id: 1 }, &form, 0.);
+
+        // ====== ASSERT ======
+        assert_eq!(draft.title, "I'm title");
+        assert_eq!(draft.tags, "rust seed");
+        let values = draft
+            .to_form()
+            .iter_fields()
+            .map(|field| field.value().to_owned())
+            .collect::<Vec<_>>();
+        assert_eq!(values, vec!["I'm title", "", "", "rust seed"]);
+    }
+
+    #[wasm_bindgen_test]
+    fn to_markdown_test() {
+        // ====== ARRANGE ======
+        let draft = ArticleDraft {
+            target: DraftTarget::New { id: 1 },
+            title: "How to train your dragon".into(),
+            description: "Ever wonder how?".into(),
+            body: "You have to believe.".into(),
+            tags: "dragons training".into(),
+            edited_at_ms: 0.,
+        };
+
+        // ====== ACT & ASSERT ======
+        assert_eq!(
+            draft.to_markdown(),
+            "# How to train your dragon\n\n> Ever wonder how?\n\nYou have to believe.\n\nTags: dragons training\n"
+        );
+        assert_eq!(draft.file_name(), "how-to-train-your-dragon.md");
+    }
+}
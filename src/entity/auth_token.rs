@@ -0,0 +1,115 @@
+use serde::Deserialize;
+
+/// The viewer is warned this long before their auth token expires.
+pub const EXPIRY_WARNING_MS: f64 = 5. * 60. * 1000.;
+
+// ------ Claims ------
+
+/// Claims of the JWT auth token issued by Conduit.
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct Claims {
+    /// Expiration time in seconds since the Unix epoch.
+    pub exp: Option<f64>,
+}
+
+impl Claims {
+    /// Decode claims of the auth token **without** verifying its signature - only the server can do it.
+    ///
+    /// Returns `None` for tokens which aren't JWTs.
+    pub fn decode(auth_token: &str) -> Option<Self> {
+        let payload = auth_token.split('.').nth(1)?;
+        let json = decode_base64_url(payload)?;
+        serde_json::from_str(&json).ok()
+    }
+
+    pub fn expires_at_ms(&self) -> Option<f64> {
+        self.exp.map(|exp| exp * 1000.)
+    }
+}
+
+/// `None` if the auth token doesn't expire or we don't know when.
+pub fn expires_at_ms(auth_token: &str) -> Option<f64> {
+    Claims::decode(auth_token).and_then(|claims| claims.expires_at_ms())
+}
+
+/// Requests with expired auth tokens are certain to fail.
+pub fn is_expired(auth_token: &str, now_ms: f64) -> bool {
+    expires_at_ms(auth_token).is_some_and(|expires_at| expires_at <= now_ms)
+}
+
+/// The auth token expires in `EXPIRY_WARNING_MS` or earlier.
+pub fn expires_soon(auth_token: &str, now_ms: f64) -> bool {
+    expires_at_ms(auth_token).is_some_and(|expires_at| expires_at - now_ms <= EXPIRY_WARNING_MS)
+}
+
+// ====== PRIVATE ======
+
+/// JWT parts are encoded with URL-safe Base64 without padding.
+fn decode_base64_url(encoded: &str) -> Option<String> {
+    let mut base64 = encoded.replace('-', "+").replace('_', "/");
+    while !base64.len().is_multiple_of(4) {
+        base64.push('=');
+    }
+    seed::window().atob(&base64).ok()
+}
+
+// ====== ====== TESTS ====== ======
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    /// Payload: `{"id":1,"username":"John","exp":1578441600}` (2020-01-08T00:00:00Z).
+    const TOKEN_WITH_EXP: &str = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9.eyJpZCI6MSwidXNlcm5hbWUiOiJKb2huIiwiZXhwIjoxNTc4NDQxNjAwfQ.signature";
+    /// Payload: `{"id":1,"username":"John"}`.
+    const TOKEN_WITHOUT_EXP: &str =
+        "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9.eyJpZCI6MSwidXNlcm5hbWUiOiJKb2huIn0.signature";
+    const EXPIRES_AT_MS: f64 = 1_578_441_600_000.;
+
+    #[wasm_bindgen_test]
+    fn decode_claims_test() {
+        // ====== ACT ======
+        let claims = Claims::decode(TOKEN_WITH_EXP);
+
+        // ====== ASSERT ======
+        assert_eq!(
+            claims,
+            Some(Claims {
+                exp: Some(1_578_441_600.)
+            })
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn decode_claims_of_opaque_token_test() {
+        // ====== ACT & ASSERT ======
+        assert!(Claims::decode("fake-token-1").is_none());
+        assert!(Claims::decode("a.%%%.c").is_none());
+    }
+
+    #[wasm_bindgen_test]
+    fn is_expired_test() {
+        // ====== ACT & ASSERT ======
+        assert!(!is_expired(TOKEN_WITH_EXP, EXPIRES_AT_MS - 1.));
+        assert!(is_expired(TOKEN_WITH_EXP, EXPIRES_AT_MS));
+        assert!(!is_expired(TOKEN_WITHOUT_EXP, EXPIRES_AT_MS));
+        assert!(!is_expired("fake-token-1", EXPIRES_AT_MS));
+    }
+
+    #[wasm_bindgen_test]
+    fn expires_soon_test() {
+        // ====== ACT & ASSERT ======
+        assert!(!expires_soon(
+            TOKEN_WITH_EXP,
+            EXPIRES_AT_MS - EXPIRY_WARNING_MS - 1.
+        ));
+        assert!(expires_soon(
+            TOKEN_WITH_EXP,
+            EXPIRES_AT_MS - EXPIRY_WARNING_MS
+        ));
+        assert!(!expires_soon(TOKEN_WITHOUT_EXP, EXPIRES_AT_MS));
+    }
+}
@@ -22,6 +22,19 @@ impl Author {
         }
     }
 
+    /// It doesn't change the viewer - they can't follow themselves.
+    pub fn set_following(&mut self, following: bool) {
+        let profile = match self {
+            Self::Following(profile) | Self::NotFollowing(profile) => profile.clone(),
+            Self::IsViewer(_) => return,
+        };
+        *self = if following {
+            Self::Following(profile)
+        } else {
+            Self::NotFollowing(profile)
+        };
+    }
+
     pub fn profile(&self) -> &Profile {
         match self {
             Self::Following(profile) | Self::NotFollowing(profile) => profile,
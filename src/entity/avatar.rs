@@ -2,7 +2,7 @@ use crate::entity::Image;
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct Avatar(Option<Cow<'static, str>>);
 
 impl Avatar {
@@ -0,0 +1,48 @@
+use crate::entity::Slug;
+use serde::{Deserialize, Serialize};
+
+// ------ CommentDraft ------
+
+/// Unsent comment of an article.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct CommentDraft {
+    pub slug: String,
+    pub body: String,
+    /// Milliseconds since the Unix epoch.
+    pub edited_at_ms: f64,
+}
+
+impl CommentDraft {
+    pub fn new(slug: &Slug, body: impl Into<String>, edited_at_ms: f64) -> Self {
+        Self {
+            slug: slug.as_str().to_owned(),
+            body: body.into(),
+            edited_at_ms,
+        }
+    }
+
+    /// The draft hasn't been edited for `max_age_ms` or longer.
+    pub fn is_expired(&self, max_age_ms: f64, now_ms: f64) -> bool {
+        now_ms - self.edited_at_ms >= max_age_ms
+    }
+}
+
+// ====== ====== TESTS ====== ======
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn is_expired_test() {
+        // ====== ARRANGE ======
+        let draft = CommentDraft::new(&Slug::from("slug".to_owned()), "Nice!", 1000.);
+
+        // ====== ACT & ASSERT ======
+        assert!(!draft.is_expired(500., 1499.));
+        assert!(draft.is_expired(500., 1500.));
+    }
+}
@@ -1,10 +1,17 @@
-static IMAGES_BASE_URL: &str = "/assets/images";
+use crate::route;
+
+static IMAGES_PATH: &str = "/assets/images";
 
 pub struct Image(String);
 
 impl Image {
     pub fn new(filename: &str) -> Self {
-        Self(format!("{}/{}", IMAGES_BASE_URL, filename))
+        Self(format!(
+            "{}{}/{}",
+            route::base_path(),
+            IMAGES_PATH,
+            filename
+        ))
     }
 
     pub fn url(&self) -> &str {
@@ -6,6 +6,14 @@ pub use article::{
     Article,
 };
 
+pub mod article_draft;
+pub use article_draft::{ArticleDraft, DraftTarget};
+
+pub mod api_error;
+pub use api_error::ApiError;
+
+pub mod auth_token;
+
 pub mod image;
 pub use image::Image;
 
@@ -15,6 +23,9 @@ pub use author::Author;
 pub mod avatar;
 pub use avatar::Avatar;
 
+pub mod comment_draft;
+pub use comment_draft::CommentDraft;
+
 pub mod error_message;
 pub use error_message::ErrorMessage;
 
@@ -29,6 +40,9 @@ pub use page_number::PageNumber;
 pub mod paginated_list;
 pub use paginated_list::PaginatedList;
 
+pub mod pending_mutation;
+pub use pending_mutation::PendingMutation;
+
 pub mod timestamp;
 pub use timestamp::Timestamp;
 
@@ -38,5 +52,8 @@ pub use username::Username;
 pub mod viewer;
 pub use viewer::Viewer;
 
+pub mod preferences;
+pub use preferences::Preferences;
+
 pub mod profile;
 pub use profile::Profile;
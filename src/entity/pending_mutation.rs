@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+// ------ PendingMutation ------
+
+/// Mutation made while offline - it's sent once the connection is back.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum PendingMutation {
+    Favorite { slug: String },
+    Unfavorite { slug: String },
+    Follow { username: String },
+    Unfollow { username: String },
+    CreateComment { slug: String, body: String },
+}
+
+impl PendingMutation {
+    pub fn description(&self) -> String {
+        use PendingMutation::*;
+        match self {
+            Favorite { slug } => format!("favorite article \"{}\"", slug),
+            Unfavorite { slug } => format!("unfavorite article \"{}\"", slug),
+            Follow { username } => format!("follow {}", username),
+            Unfollow { username } => format!("unfollow {}", username),
+            CreateComment { slug, .. } => format!("post comment to article \"{}\"", slug),
+        }
+    }
+}
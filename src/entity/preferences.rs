@@ -0,0 +1,146 @@
+use serde::{Deserialize, Serialize};
+use std::num::{NonZeroU32, NonZeroUsize};
+use strum_macros::EnumIter;
+
+/// Comment drafts which haven't been edited for this many days are discarded.
+pub const DEFAULT_COMMENT_DRAFT_MAX_AGE_DAYS: u32 = 7;
+
+// ------ Preferences ------
+
+/// Choices of the viewer (or the guest) which are kept in the browser.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Preferences {
+    pub home_feed: HomeFeed,
+    /// `None` - each feed uses its own default.
+    pub articles_per_page: Option<NonZeroUsize>,
+    pub date_format: DateFormat,
+    pub theme: Theme,
+    /// `None` - `DEFAULT_COMMENT_DRAFT_MAX_AGE_DAYS`.
+    pub comment_draft_max_age_days: Option<NonZeroU32>,
+}
+
+impl Preferences {
+    pub fn comment_draft_max_age_ms(&self) -> f64 {
+        let days = self
+            .comment_draft_max_age_days
+            .map_or(DEFAULT_COMMENT_DRAFT_MAX_AGE_DAYS, NonZeroU32::get);
+        f64::from(days) * 24. * 60. * 60. * 1000.
+    }
+}
+
+// ------ HomeFeed ------
+
+/// Tab selected when the home page is opened.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize, EnumIter)]
+pub enum HomeFeed {
+    /// The global feed is used for guests.
+    #[default]
+    Your,
+    Global,
+}
+
+impl HomeFeed {
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Your => "Your Feed",
+            Self::Global => "Global Feed",
+        }
+    }
+}
+
+// ------ DateFormat ------
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize, EnumIter)]
+pub enum DateFormat {
+    /// "February 14, 2018"
+    #[default]
+    Long,
+    /// "2018-02-14"
+    Iso,
+}
+
+impl DateFormat {
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Long => "February 14, 2018",
+            Self::Iso => "2018-02-14",
+        }
+    }
+
+    /// Format string for `chrono`.
+    pub const fn pattern(self) -> &'static str {
+        match self {
+            Self::Long => "%B %-d, %-Y",
+            Self::Iso => "%Y-%m-%d",
+        }
+    }
+}
+
+// ------ Theme ------
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize, EnumIter)]
+pub enum Theme {
+    #[default]
+    Light,
+    Dark,
+}
+
+impl Theme {
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Light => "Light",
+            Self::Dark => "Dark",
+        }
+    }
+
+    /// Class of the `body` element.
+    pub const fn class_name(self) -> &'static str {
+        match self {
+            Self::Light => "theme-light",
+            Self::Dark => "theme-dark",
+        }
+    }
+}
+
+// ====== ====== TESTS ====== ======
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn missing_fields_have_default_values_test() {
+        // ====== ACT ======
+        let preferences: Preferences =
+            serde_json::from_str(r#"{"theme":"Dark"}"#).expect("deserialization failed");
+
+        // ====== ASSERT ======
+        assert_eq!(
+            preferences,
+            Preferences {
+                theme: Theme::Dark,
+                ..Preferences::default()
+            }
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn comment_draft_max_age_ms_test() {
+        // ====== ARRANGE ======
+        let preferences = Preferences {
+            comment_draft_max_age_days: NonZeroU32::new(1),
+            ..Preferences::default()
+        };
+
+        // ====== ACT & ASSERT ======
+        assert_eq!(preferences.comment_draft_max_age_ms(), 86_400_000.);
+        assert_eq!(
+            Preferences::default().comment_draft_max_age_ms(),
+            7. * 86_400_000.
+        );
+    }
+}
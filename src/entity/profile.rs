@@ -1,7 +1,7 @@
 use crate::entity::{Avatar, Username};
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct Profile {
     pub bio: Option<String>,
     pub avatar: Avatar,
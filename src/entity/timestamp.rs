@@ -1,4 +1,4 @@
-use crate::entity::ErrorMessage;
+use crate::entity::{preferences::DateFormat, ErrorMessage};
 use chrono::prelude::*;
 use newtype::NewType;
 use seed::prelude::*;
@@ -22,11 +22,10 @@ impl TryFrom<String> for Timestamp {
 
 // ------ view timestamp ------
 
-pub fn view<Ms>(timestamp: &Timestamp) -> Node<Ms> {
+pub fn view<Ms>(timestamp: &Timestamp, date_format: DateFormat) -> Node<Ms> {
     span![
         class!["date"],
-        // "February 14, 2018"
-        timestamp.format("%B %-d, %-Y").to_string()
+        timestamp.format(date_format.pattern()).to_string()
     ]
 }
 
@@ -49,9 +48,24 @@ pub mod tests {
             .expect("cannot parse given timestamp");
 
         // ====== ACT ======
-        let node: Node<()> = view(&timestamp);
+        let node: Node<()> = view(&timestamp, DateFormat::Long);
 
         // ====== ASSERT ======
         assert_eq!(node.get_text(), "July 24, 2019");
     }
+
+    #[wasm_bindgen_test]
+    fn view_timestamp_in_iso_format_test() {
+        // ====== ARRANGE ======
+        let timestamp: Timestamp = "2019-07-24T08:21:36.453Z"
+            .to_string()
+            .try_into()
+            .expect("cannot parse given timestamp");
+
+        // ====== ACT ======
+        let node: Node<()> = view(&timestamp, DateFormat::Iso);
+
+        // ====== ASSERT ======
+        assert_eq!(node.get_text(), "2019-07-24");
+    }
 }
@@ -4,7 +4,7 @@ use crate::{
 };
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 pub struct Viewer {
     pub profile: Profile,
     pub auth_token: String,
@@ -19,6 +19,11 @@ impl Viewer {
         &self.profile.avatar
     }
 
+    /// Both viewers belong to the same account, although one of them may be outdated.
+    pub fn is_same_account(&self, other: &Self) -> bool {
+        self.username() == other.username() || self.auth_token == other.auth_token
+    }
+
     pub fn store(&self) {
         storage::store_viewer(self);
     }
@@ -9,10 +9,135 @@ pub fn take<T: Default>(source: &mut T) -> T {
 #[cfg(test)]
 pub mod tests {
     use super::*;
+    use seed::{app::OrdersProxy, prelude::*};
+    use std::{future::Future, pin::Pin};
     use wasm_bindgen_test::*;
 
     wasm_bindgen_test_configure!(run_in_browser);
 
+    type Cmd<Ms> = Pin<Box<dyn Future<Output = Result<Ms, Ms>>>>;
+    type AfterRender<Ms> = Box<dyn FnOnce(Option<RenderTimestampDelta>) -> Ms>;
+
+    /// Records orders made by a module's `update`, so tests can run its commands.
+    ///
+    /// `proxy` and `clone_app` can't be recorded - Seed creates proxies and apps only
+    /// for a running app. Test child modules (e.g. `article::feed`) with their own
+    /// `TestOrders` instead of going through the parent.
+    pub(crate) struct TestOrders<Ms, GMs> {
+        pub cmds: Vec<Cmd<Ms>>,
+        pub msgs: Vec<Ms>,
+        pub g_cmds: Vec<Cmd<GMs>>,
+        pub g_msgs: Vec<GMs>,
+        pub after_render: Vec<AfterRender<Ms>>,
+    }
+
+    impl<Ms, GMs> Default for TestOrders<Ms, GMs> {
+        fn default() -> Self {
+            Self {
+                cmds: Vec::new(),
+                msgs: Vec::new(),
+                g_cmds: Vec::new(),
+                g_msgs: Vec::new(),
+                after_render: Vec::new(),
+            }
+        }
+    }
+
+    impl<Ms: 'static, GMs: 'static> TestOrders<Ms, GMs> {
+        /// Run the scheduled commands and return their messages.
+        pub async fn run_cmds(&mut self) -> Vec<Ms> {
+            run(take(&mut self.cmds)).await
+        }
+
+        /// Run the scheduled global commands and return their messages.
+        pub async fn run_g_cmds(&mut self) -> Vec<GMs> {
+            run(take(&mut self.g_cmds)).await
+        }
+
+        /// Simulate a render - invoke `after_next_render` callbacks and return their messages.
+        pub fn render_now(&mut self) -> Vec<Ms> {
+            take(&mut self.after_render)
+                .into_iter()
+                .map(|callback| callback(None))
+                .collect()
+        }
+    }
+
+    async fn run<Ms>(cmds: Vec<Cmd<Ms>>) -> Vec<Ms> {
+        let mut msgs = Vec::new();
+        for cmd in cmds {
+            msgs.push(cmd.await.unwrap_or_else(|msg| msg));
+        }
+        msgs
+    }
+
+    impl<Ms: 'static, GMs: 'static> Orders<Ms, GMs> for TestOrders<Ms, GMs> {
+        type AppMs = Ms;
+        type Mdl = ();
+        type ElC = Node<Ms>;
+
+        fn proxy<ChildMs: 'static>(
+            &mut self,
+            _: impl FnOnce(ChildMs) -> Ms + 'static + Clone,
+        ) -> OrdersProxy<'_, ChildMs, Ms, (), Node<Ms>, GMs> {
+            unimplemented!("TestOrders can't create proxies - test the child module directly")
+        }
+
+        fn render(&mut self) -> &mut Self {
+            self
+        }
+
+        fn force_render_now(&mut self) -> &mut Self {
+            self
+        }
+
+        fn skip(&mut self) -> &mut Self {
+            self
+        }
+
+        fn send_msg(&mut self, msg: Ms) -> &mut Self {
+            self.msgs.push(msg);
+            self
+        }
+
+        fn perform_cmd<C>(&mut self, cmd: C) -> &mut Self
+        where
+            C: Future<Output = Result<Ms, Ms>> + 'static,
+        {
+            self.cmds.push(Box::pin(cmd));
+            self
+        }
+
+        fn send_g_msg(&mut self, g_msg: GMs) -> &mut Self {
+            self.g_msgs.push(g_msg);
+            self
+        }
+
+        fn perform_g_cmd<C>(&mut self, g_cmd: C) -> &mut Self
+        where
+            C: Future<Output = Result<GMs, GMs>> + 'static,
+        {
+            self.g_cmds.push(Box::pin(g_cmd));
+            self
+        }
+
+        fn clone_app(&self) -> App<Ms, (), Node<Ms>, GMs> {
+            unimplemented!("TestOrders has no app - test the child module directly")
+        }
+
+        fn msg_mapper(&self) -> Box<dyn Fn(Ms) -> Ms> {
+            Box::new(|msg| msg)
+        }
+
+        fn after_next_render(
+            &mut self,
+            callback: impl FnOnce(Option<RenderTimestampDelta>) -> Ms + 'static,
+        ) -> &mut Self {
+            self.after_render.push(Box::new(callback));
+            self
+        }
+    }
+
     #[wasm_bindgen_test]
     fn take_test() {
         // ====== ARRANGE ======
@@ -25,4 +150,20 @@ pub mod tests {
         assert_eq!(text, "");
         assert_eq!(taken_text, "something");
     }
+
+    #[wasm_bindgen_test]
+    async fn test_orders_records_g_cmds_and_after_render_test() {
+        // ====== ARRANGE ======
+        let mut orders = TestOrders::<&str, &str>::default();
+
+        // ====== ACT ======
+        orders
+            .perform_g_cmd(async { Ok("global") })
+            .after_next_render(|_| "rendered");
+
+        // ====== ASSERT ======
+        assert_eq!(orders.run_g_cmds().await, vec!["global"]);
+        assert_eq!(orders.render_now(), vec!["rendered"]);
+        assert!(orders.after_render.is_empty());
+    }
 }
@@ -7,19 +7,22 @@
 
 #[macro_use]
 extern crate seed;
-use entity::{article, username};
+use entity::{article, auth_token, username};
 use helper::take;
+use route::HomeQuery;
 use seed::prelude::*;
 use std::convert::TryInto;
 
 pub use route::Route;
 pub use session::Session;
 
+mod api;
 mod coder;
 mod entity;
 mod helper;
 mod loading;
 mod logger;
+mod offline;
 mod page;
 mod request;
 mod route;
@@ -40,6 +43,24 @@ enum Model<'a> {
     Profile(page::profile::Model<'a>, username::Username<'a>),
     Article(page::article::Model),
     ArticleEditor(page::article_editor::Model, Option<article::slug::Slug>),
+    Drafts(page::drafts::Model),
+}
+
+impl<'a> Model<'a> {
+    fn session(&self) -> &Session {
+        use Model::*;
+        match self {
+            Redirect(session) | NotFound(session) => session,
+            Home(model) => model.session(),
+            Settings(model) => model.session(),
+            Login(model) => model.session(),
+            Register(model) => model.session(),
+            Profile(model, _) => model.session(),
+            Article(model) => model.session(),
+            ArticleEditor(model, _) => model.session(),
+            Drafts(model) => model.session(),
+        }
+    }
 }
 
 impl<'a> Default for Model<'a> {
@@ -60,6 +81,7 @@ impl<'a> From<Model<'a>> for Session {
             Profile(model, _) => model.into(),
             Article(model) => model.into(),
             ArticleEditor(model, _) => model.into(),
+            Drafts(model) => model.into(),
         }
     }
 }
@@ -82,9 +104,29 @@ fn after_mount(
     url: Url,
     orders: &mut impl Orders<Msg<'static>, GMsg>,
 ) -> AfterMount<Model<'static>> {
+    request::init_base_url();
+    route::init();
     orders.send_msg(Msg::RouteChanged(url.try_into().ok()));
 
-    let model = Model::Redirect(Session::new(storage::load_viewer()));
+    let api = api::for_base_url(&request::base_url());
+    let session = Session::new(storage::load_viewer(), api).with_accounts(storage::load_viewers());
+    page::apply_theme(session.preferences().theme);
+    watch_auth_token_expiry(&session, orders);
+    if let Some(viewer) = session.viewer() {
+        // The stored viewer may be outdated - e.g. changed in another browser.
+        let username = viewer.username().to_static();
+        orders.perform_cmd(api::cmd(session.api().load_viewer(viewer), |result| {
+            Msg::ViewerLoadCompleted(username, result)
+        }));
+    }
+    if offline::is_online() {
+        orders.perform_cmd(offline::replay(
+            session.clone(),
+            Msg::PendingMutationsReplayed,
+        ));
+    }
+
+    let model = Model::Redirect(session);
     AfterMount::new(model).url_handling(UrlHandling::None)
 }
 
@@ -95,25 +137,38 @@ fn after_mount(
 pub enum GMsg {
     RoutePushed(Route<'static>),
     SessionChanged(Session),
+    PendingMutationsReplayed(offline::ReplayReport),
 }
 
 fn sink<'a>(g_msg: GMsg, model: &mut Model<'a>, orders: &mut impl Orders<Msg<'static>, GMsg>) {
-    if let GMsg::RoutePushed(ref route) = g_msg {
-        orders.send_msg(Msg::RouteChanged(Some(route.clone())));
+    match &g_msg {
+        GMsg::RoutePushed(route) => {
+            orders.send_msg(Msg::RouteChanged(Some(route.clone())));
+        }
+        GMsg::SessionChanged(session) => {
+            page::apply_theme(session.preferences().theme);
+            // The timers of the current token are already running.
+            let auth_token =
+                |session: &Session| session.viewer().map(|viewer| viewer.auth_token.clone());
+            if auth_token(session) != auth_token(model.session()) {
+                watch_auth_token_expiry(session, orders);
+            }
+        }
+        _ => (),
     }
 
     match model {
         Model::NotFound(_) | Model::Redirect(_) => {
             if let GMsg::SessionChanged(session) = g_msg {
                 *model = Model::Redirect(session);
-                route::go_to(Route::Home, orders);
+                route::go_to(Route::Home(HomeQuery::default()), orders);
             }
         }
         Model::Settings(model) => {
             page::settings::sink(g_msg, model, &mut orders.proxy(Msg::SettingsMsg));
         }
         Model::Home(model) => {
-            page::home::sink(g_msg, model);
+            page::home::sink(g_msg, model, &mut orders.proxy(Msg::HomeMsg));
         }
         Model::Login(model) => {
             page::login::sink(g_msg, model, &mut orders.proxy(Msg::LoginMsg));
@@ -130,6 +185,9 @@ fn sink<'a>(g_msg: GMsg, model: &mut Model<'a>, orders: &mut impl Orders<Msg<'st
         Model::ArticleEditor(model, _) => {
             page::article_editor::sink(g_msg, model, &mut orders.proxy(Msg::ArticleEditorMsg));
         }
+        Model::Drafts(model) => {
+            page::drafts::sink(g_msg, model, &mut orders.proxy(Msg::DraftsMsg));
+        }
     }
 }
 
@@ -140,6 +198,21 @@ fn sink<'a>(g_msg: GMsg, model: &mut Model<'a>, orders: &mut impl Orders<Msg<'st
 #[allow(clippy::enum_variant_names)]
 enum Msg<'a> {
     RouteChanged(Option<Route<'a>>),
+    /// Contains the rejected auth token.
+    Unauthorized(String),
+    WentOnline,
+    PendingMutationsReplayed(offline::ReplayReport),
+    AccountActionClicked(page::AccountAction),
+    /// Contains the username of the viewer whose data have been requested.
+    ViewerLoadCompleted(
+        username::Username<'static>,
+        Result<entity::Viewer, entity::ApiError>,
+    ),
+    AuthTokenExpiring,
+    /// Contains the expired auth token.
+    AuthTokenExpired(String),
+    /// `None` when the whole storage has been cleared.
+    StorageChanged(Option<String>),
     HomeMsg(page::home::Msg),
     SettingsMsg(page::settings::Msg),
     LoginMsg(page::login::Msg),
@@ -147,6 +220,7 @@ enum Msg<'a> {
     ProfileMsg(page::profile::Msg),
     ArticleMsg(page::article::Msg),
     ArticleEditorMsg(page::article_editor::Msg),
+    DraftsMsg(page::drafts::Msg),
 }
 
 fn update<'a>(msg: Msg<'a>, model: &mut Model<'a>, orders: &mut impl Orders<Msg<'static>, GMsg>) {
@@ -154,6 +228,53 @@ fn update<'a>(msg: Msg<'a>, model: &mut Model<'a>, orders: &mut impl Orders<Msg<
         Msg::RouteChanged(route) => {
             change_model_by_route(route, model, orders);
         }
+        Msg::Unauthorized(auth_token) => {
+            sign_out_unauthorized(&auth_token, model, orders);
+        }
+        Msg::WentOnline => {
+            orders.perform_cmd(offline::replay(
+                model.session().clone(),
+                Msg::PendingMutationsReplayed,
+            ));
+        }
+        Msg::PendingMutationsReplayed(report) => {
+            if report.sent > 0 || !report.conflicts.is_empty() {
+                orders.send_g_msg(GMsg::PendingMutationsReplayed(report));
+            }
+        }
+        Msg::AccountActionClicked(page::AccountAction::Switch(username)) => {
+            switch_account(&username, model, orders);
+        }
+        Msg::AccountActionClicked(page::AccountAction::SignOut(username)) => {
+            storage::delete_viewer(&username);
+            orders.send_g_msg(GMsg::SessionChanged(model.session().signed_out(&username)));
+        }
+        Msg::ViewerLoadCompleted(username, Ok(viewer)) => {
+            let session = model.session();
+            // Ignore the response if another account has been activated in the meantime.
+            if let Some(old_viewer) = session.viewer() {
+                if *old_viewer.username() == username && *old_viewer != viewer {
+                    viewer.store();
+                    orders.send_g_msg(GMsg::SessionChanged(session.logged_in(viewer)));
+                }
+            }
+        }
+        Msg::ViewerLoadCompleted(_, Err(entity::ApiError::Unauthorized)) => {
+            // The viewer is signed out by `Msg::Unauthorized`.
+        }
+        Msg::ViewerLoadCompleted(_, Err(error)) => {
+            // Keep using the stored viewer - e.g. the app is offline.
+            logger::error(error);
+        }
+        Msg::AuthTokenExpiring => {
+            // Nothing to do - the header shows the warning when it's rendered again.
+        }
+        Msg::AuthTokenExpired(auth_token) => {
+            handle_auth_token_expiry(auth_token, model, orders);
+        }
+        Msg::StorageChanged(key) => {
+            sync_session_with_storage(key.as_deref(), model, orders);
+        }
         Msg::HomeMsg(module_msg) => {
             if let Model::Home(module_model) = model {
                 page::home::update(module_msg, module_model, &mut orders.proxy(Msg::HomeMsg));
@@ -201,23 +322,144 @@ fn update<'a>(msg: Msg<'a>, model: &mut Model<'a>, orders: &mut impl Orders<Msg<
                 );
             }
         }
+        Msg::DraftsMsg(module_msg) => {
+            if let Model::Drafts(module_model) = model {
+                page::drafts::update(module_msg, module_model, &mut orders.proxy(Msg::DraftsMsg));
+            }
+        }
+    }
+}
+
+/// Sign out the active viewer if the server has rejected their auth token
+/// and ask them to sign in again.
+fn sign_out_unauthorized(
+    auth_token: &str,
+    model: &mut Model,
+    orders: &mut impl Orders<Msg<'static>, GMsg>,
+) {
+    // Ignore e.g. late responses to requests of the previously active account.
+    let username = match model.session().viewer() {
+        Some(viewer) if viewer.auth_token == auth_token => viewer.username().to_static(),
+        _ => return,
+    };
+    storage::delete_viewer(&username);
+    if let Model::ArticleEditor(editor_model, _) = model {
+        page::article_editor::save_unsaved_draft(editor_model);
+    }
+    let guest = model.session().guest();
+    let return_route: Option<Route<'static>> = seed::browser::url::current().try_into().ok();
+    *model = Model::Login(page::login::init_after_session_expired(
+        guest.clone(),
+        return_route.clone(),
+    ));
+    route::push(Route::Login(return_route.map(Box::new)));
+    orders.send_g_msg(GMsg::SessionChanged(guest));
+}
+
+/// Make the stored viewer active and send their pending mutations.
+fn switch_account(
+    username: &username::Username,
+    model: &Model,
+    orders: &mut impl Orders<Msg<'static>, GMsg>,
+) {
+    if let Some(viewer) = storage::activate_viewer(username) {
+        let session = model.session().logged_in(viewer);
+        if offline::is_online() {
+            orders.perform_cmd(offline::replay(
+                session.clone(),
+                Msg::PendingMutationsReplayed,
+            ));
+        }
+        orders.send_g_msg(GMsg::SessionChanged(session));
+    }
+}
+
+/// Sign out the viewer if the token is still used; `auth_token` is the expired one.
+fn handle_auth_token_expiry(
+    auth_token: String,
+    model: &Model,
+    orders: &mut impl Orders<Msg<'static>, GMsg>,
+) {
+    let is_current_token = model
+        .session()
+        .viewer()
+        .is_some_and(|viewer| viewer.auth_token == auth_token);
+    if is_current_token {
+        if auth_token::is_expired(&auth_token, js_sys::Date::now()) {
+            orders.send_msg(Msg::Unauthorized(auth_token));
+        } else {
+            // The timeout has been shortened to `MAX_TIMEOUT_MS`.
+            watch_auth_token_expiry(model.session(), orders);
+        }
+    }
+}
+
+/// Another tab has signed in, signed out or updated the viewer.
+fn sync_session_with_storage(
+    key: Option<&str>,
+    model: &Model,
+    orders: &mut impl Orders<Msg<'static>, GMsg>,
+) {
+    if storage::is_viewers_key(key) {
+        let viewer = storage::load_viewer();
+        let accounts = storage::load_viewers();
+        let session = model.session();
+        if viewer.as_ref() != session.viewer() || accounts.as_slice() != session.accounts() {
+            orders.send_g_msg(GMsg::SessionChanged(session.synced(viewer, accounts)));
+        }
+    }
+}
+
+/// Schedule the warning and the sign-out before the viewer's auth token expires.
+///
+/// Expiries beyond `MAX_TIMEOUT_MS` are checked again when the shortened timeout passes.
+fn watch_auth_token_expiry(session: &Session, orders: &mut impl Orders<Msg<'static>, GMsg>) {
+    let auth_token = match session.viewer() {
+        Some(viewer) => viewer.auth_token.clone(),
+        None => return,
+    };
+    if let Some(expires_at) = auth_token::expires_at_ms(&auth_token) {
+        let expires_in = expires_at - js_sys::Date::now();
+        let warn_in = expires_in - auth_token::EXPIRY_WARNING_MS;
+        if warn_in <= MAX_TIMEOUT_MS {
+            orders.perform_cmd(after(warn_in, Msg::AuthTokenExpiring));
+        }
+        orders.perform_cmd(after(
+            expires_in.min(MAX_TIMEOUT_MS),
+            Msg::AuthTokenExpired(auth_token),
+        ));
     }
 }
 
+/// `setTimeout` treats longer delays as negative ones and fires immediately.
+const MAX_TIMEOUT_MS: f64 = i32::MAX as f64;
+
+async fn after<Ms>(delay_ms: f64, msg: Ms) -> Result<Ms, Ms> {
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let delay_ms = delay_ms.clamp(0., MAX_TIMEOUT_MS) as u32;
+    gloo_timers::future::TimeoutFuture::new(delay_ms).await;
+    Ok(msg)
+}
+
 fn change_model_by_route<'a>(
     route: Option<Route<'a>>,
     model: &mut Model<'a>,
     orders: &mut impl Orders<Msg<'static>, GMsg>,
 ) {
+    let guest = model.session().guest();
+    let is_guest = model.session().viewer().is_none();
     let mut session = || Session::from(take(model));
     match route {
         None => *model = Model::NotFound(session()),
+        Some(route) if route.requires_viewer() && is_guest => {
+            route::go_to(Route::Login(Some(Box::new(route.to_static()))), orders);
+        }
         Some(route) => match route {
-            Route::Root => route::go_to(Route::Home, orders),
+            Route::Root => route::go_to(Route::Home(HomeQuery::default()), orders),
             Route::Logout => {
-                storage::delete_app_data();
-                orders.send_g_msg(GMsg::SessionChanged(Session::Guest));
-                route::go_to(Route::Home, orders)
+                storage::delete_active_viewer();
+                orders.send_g_msg(GMsg::SessionChanged(guest));
+                route::go_to(Route::Home(HomeQuery::default()), orders)
             }
             Route::NewArticle => {
                 *model = Model::ArticleEditor(page::article_editor::init_new(session()), None);
@@ -232,26 +474,55 @@ fn change_model_by_route<'a>(
                     Some(slug),
                 );
             }
+            Route::Drafts => {
+                *model = Model::Drafts(page::drafts::init(session()));
+            }
+            Route::Draft(id) => {
+                *model =
+                    Model::ArticleEditor(page::article_editor::init_draft(session(), &id), None);
+            }
             Route::Settings => {
                 *model = Model::Settings(page::settings::init(
                     session(),
                     &mut orders.proxy(Msg::SettingsMsg),
                 ));
             }
-            Route::Home => {
-                *model = Model::Home(page::home::init(session(), &mut orders.proxy(Msg::HomeMsg)));
+            Route::Home(query) => {
+                *model = Model::Home(page::home::init(
+                    session(),
+                    query,
+                    &mut orders.proxy(Msg::HomeMsg),
+                ));
             }
-            Route::Login => {
-                *model = Model::Login(page::login::init(session()));
+            Route::Login(redirect) => {
+                *model = Model::Login(page::login::init(
+                    session(),
+                    redirect.map(|redirect| redirect.to_static()),
+                ));
             }
-            Route::Register => {
-                *model = Model::Register(page::register::init(session()));
+            Route::Register(redirect) => {
+                *model = Model::Register(page::register::init(
+                    session(),
+                    redirect.map(|redirect| redirect.to_static()),
+                ));
             }
             Route::Profile(username) => {
                 *model = Model::Profile(
                     page::profile::init(
                         session(),
                         username.to_static(),
+                        page::profile::SelectedFeed::MyArticles,
+                        &mut orders.proxy(Msg::ProfileMsg),
+                    ),
+                    username.into_owned(),
+                );
+            }
+            Route::ProfileFavorites(username) => {
+                *model = Model::Profile(
+                    page::profile::init(
+                        session(),
+                        username.to_static(),
+                        page::profile::SelectedFeed::FavoritedArticles,
                         &mut orders.proxy(Msg::ProfileMsg),
                     ),
                     username.into_owned(),
@@ -274,34 +545,59 @@ fn change_model_by_route<'a>(
 
 fn view(model: &Model) -> impl View<Msg<'static>> {
     use page::Page;
-    match model {
-        Model::Redirect(session) => Page::Other.view(page::blank::view(), session.viewer()),
-        Model::NotFound(session) => Page::Other.view(page::not_found::view(), session.viewer()),
-        Model::Settings(model) => Page::Settings
-            .view(page::settings::view(model), model.session().viewer())
-            .map_msg(Msg::SettingsMsg),
-        Model::Home(model) => Page::Home
-            .view(page::home::view(model), model.session().viewer())
-            .map_msg(Msg::HomeMsg),
-        Model::Login(model) => Page::Login
-            .view(page::login::view(model), model.session().viewer())
-            .map_msg(Msg::LoginMsg),
-        Model::Register(model) => Page::Register
-            .view(page::register::view(model), model.session().viewer())
-            .map_msg(Msg::RegisterMsg),
-        Model::Profile(model, username) => Page::Profile(username)
-            .view(page::profile::view(model), model.session().viewer())
-            .map_msg(Msg::ProfileMsg),
-        Model::Article(model) => Page::Other
-            .view(page::article::view(model), model.session().viewer())
-            .map_msg(Msg::ArticleMsg),
-        Model::ArticleEditor(model, None) => Page::NewArticle
-            .view(page::article_editor::view(model), model.session().viewer())
-            .map_msg(Msg::ArticleEditorMsg),
-        Model::ArticleEditor(model, Some(_)) => Page::Other
-            .view(page::article_editor::view(model), model.session().viewer())
-            .map_msg(Msg::ArticleEditorMsg),
-    }
+    let (page, view_page) = match model {
+        Model::Redirect(_) => (Page::Other, page::blank::view()),
+        Model::NotFound(_) => (Page::Other, page::not_found::view()),
+        Model::Settings(model) => (
+            Page::Settings,
+            page::settings::view(model).map_msg(Msg::SettingsMsg),
+        ),
+        Model::Home(model) => (Page::Home, page::home::view(model).map_msg(Msg::HomeMsg)),
+        Model::Login(model) => (Page::Login, page::login::view(model).map_msg(Msg::LoginMsg)),
+        Model::Register(model) => (
+            Page::Register,
+            page::register::view(model).map_msg(Msg::RegisterMsg),
+        ),
+        Model::Profile(model, username) => (
+            Page::Profile(username),
+            page::profile::view(model).map_msg(Msg::ProfileMsg),
+        ),
+        Model::Article(model) => (
+            Page::Other,
+            page::article::view(model).map_msg(Msg::ArticleMsg),
+        ),
+        Model::ArticleEditor(model, None) => (
+            Page::NewArticle,
+            page::article_editor::view(model).map_msg(Msg::ArticleEditorMsg),
+        ),
+        Model::ArticleEditor(model, Some(_)) => (
+            Page::Other,
+            page::article_editor::view(model).map_msg(Msg::ArticleEditorMsg),
+        ),
+        Model::Drafts(model) => (
+            Page::Drafts,
+            page::drafts::view(model).map_msg(Msg::DraftsMsg),
+        ),
+    };
+    page.view(view_page, model.session(), Msg::AccountActionClicked)
+}
+
+// ------ ------
+// Window Events
+// ------ ------
+
+fn window_events(_: &Model) -> Vec<Listener<Msg<'static>>> {
+    vec![
+        raw_ev(Ev::Online, |_| Msg::WentOnline),
+        // Fired only for changes made by other tabs.
+        raw_ev("storage", |event| {
+            Msg::StorageChanged(
+                event
+                    .dyn_ref::<web_sys::StorageEvent>()
+                    .and_then(web_sys::StorageEvent::key),
+            )
+        }),
+    ]
 }
 
 // ------ ------
@@ -310,10 +606,23 @@ fn view(model: &Model) -> impl View<Msg<'static>> {
 
 #[wasm_bindgen(start)]
 pub fn start() {
-    App::builder(update, view)
+    request::instrumentation::init();
+
+    let app = App::builder(update, view)
         .before_mount(before_mount)
         .after_mount(after_mount)
-        .routes(|url| Some(Msg::RouteChanged(url.try_into().ok())))
+        .window_events(window_events)
+        .routes(|url| route::is_new_location(&url).then(|| Msg::RouteChanged(url.try_into().ok())))
         .sink(sink)
         .build_and_start();
+
+    api::set_unauthorized_handler(move |auth_token| app.update(Msg::Unauthorized(auth_token)));
+}
+
+/// Recent API calls as JSON - call `request_log()` from the browser console
+/// to find slow or failing endpoints.
+#[wasm_bindgen]
+pub fn request_log() -> String {
+    serde_json::to_string_pretty(&request::instrumentation::recent_records())
+        .unwrap_or_else(|error| error.to_string())
 }
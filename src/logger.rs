@@ -1,11 +1,13 @@
-use std::fmt::Debug;
+use std::fmt::{Debug, Display};
 
 pub fn error(error: impl Debug) {
     error!("App error:", error)
 }
 
-pub fn errors(errors: impl IntoIterator<Item = impl Debug>) {
-    for item in errors {
-        error(item)
-    }
+pub fn info(message: impl Display) {
+    log!(message.to_string())
+}
+
+pub fn warning(message: impl Display) {
+    web_sys::console::warn_1(&message.to_string().into())
 }
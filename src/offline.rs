@@ -0,0 +1,240 @@
+use crate::{
+    api::{self, ApiFuture, ConduitApi},
+    entity::{ApiError, ErrorMessage, PendingMutation, Slug, Username, Viewer},
+    storage, Session,
+};
+use seed::prelude::*;
+use std::cell::Cell;
+
+thread_local! {
+    static REPLAYING: Cell<bool> = const { Cell::new(false) };
+}
+
+pub fn is_online() -> bool {
+    seed::window().navigator().on_line()
+}
+
+/// Connection errors mean the mutation may be sent again later.
+pub fn is_connection_error(error: &ApiError) -> bool {
+    matches!(error, ApiError::Network | ApiError::Timeout)
+}
+
+/// Add the mutation to the queue of the active viewer.
+pub fn enqueue(pending_mutation: PendingMutation) {
+    let username = active_username();
+    let mut pending_mutations = storage::load_pending_mutations(username.as_ref());
+    pending_mutations.push(pending_mutation);
+    storage::store_pending_mutations(username.as_ref(), &pending_mutations);
+}
+
+/// Pending mutations of the active viewer.
+pub fn pending_mutations() -> Vec<PendingMutation> {
+    storage::load_pending_mutations(active_username().as_ref())
+}
+
+/// `Some(favorited)` if there is a pending favorite change of the article.
+pub fn pending_favorite(slug: &Slug) -> Option<bool> {
+    pending_mutations()
+        .iter()
+        .rev()
+        .find_map(|pending_mutation| match pending_mutation {
+            PendingMutation::Favorite { slug: pending_slug } if pending_slug == slug.as_str() => {
+                Some(true)
+            }
+            PendingMutation::Unfavorite { slug: pending_slug } if pending_slug == slug.as_str() => {
+                Some(false)
+            }
+            _ => None,
+        })
+}
+
+/// `Some(following)` if there is a pending follow change of the user.
+pub fn pending_follow(username: &Username) -> Option<bool> {
+    pending_mutations()
+        .iter()
+        .rev()
+        .find_map(|pending_mutation| match pending_mutation {
+            PendingMutation::Follow {
+                username: pending_username,
+            } if pending_username == username.as_str() => Some(true),
+            PendingMutation::Unfollow {
+                username: pending_username,
+            } if pending_username == username.as_str() => Some(false),
+            _ => None,
+        })
+}
+
+/// Bodies of comments waiting to be posted to the article.
+pub fn pending_comments(slug: &Slug) -> Vec<String> {
+    pending_mutations()
+        .into_iter()
+        .filter_map(|pending_mutation| match pending_mutation {
+            PendingMutation::CreateComment {
+                slug: pending_slug,
+                body,
+            } if pending_slug == slug.as_str() => Some(body),
+            _ => None,
+        })
+        .collect()
+}
+
+// ------ ReplayReport ------
+
+#[derive(Default)]
+pub struct ReplayReport {
+    pub sent: usize,
+    /// Rejected mutations (e.g. the article has been deleted in the meantime); they are dropped.
+    pub conflicts: Vec<(PendingMutation, ApiError)>,
+}
+
+impl ReplayReport {
+    pub fn conflict_messages(&self) -> Vec<ErrorMessage> {
+        self.conflicts
+            .iter()
+            .map(|(pending_mutation, error)| {
+                format!(
+                    "Couldn't {}: {}",
+                    pending_mutation.description(),
+                    error
+                        .clone()
+                        .into_error_messages()
+                        .into_iter()
+                        .map(ErrorMessage::into_inner)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+                .into()
+            })
+            .collect()
+    }
+}
+
+/// Send pending mutations one by one in the order they were made.
+///
+/// It stops on the first connection error - the rest stays in the queue for the next attempt.
+/// Only the queue of the session's viewer is replayed - even if another account is activated
+/// in the meantime.
+pub async fn replay<Ms>(session: Session, f: fn(ReplayReport) -> Ms) -> Result<Ms, Ms> {
+    let mut report = ReplayReport::default();
+    if REPLAYING.with(|replaying| replaying.replace(true)) {
+        return Ok(f(report));
+    }
+
+    let username = session.viewer().map(|viewer| viewer.username().to_static());
+    while let Some(pending_mutation) = storage::load_pending_mutations(username.as_ref())
+        .first()
+        .cloned()
+    {
+        let result = send(session.api(), session.viewer().cloned(), &pending_mutation).await;
+        if let Err(error) = &result {
+            api::handle_unauthorized(session.viewer(), error);
+        }
+        match result {
+            Err(error) if is_connection_error(&error) => break,
+            Err(error) => report.conflicts.push((pending_mutation, error)),
+            Ok(()) => report.sent += 1,
+        }
+        let mut pending_mutations = storage::load_pending_mutations(username.as_ref());
+        pending_mutations.remove(0);
+        storage::store_pending_mutations(username.as_ref(), &pending_mutations);
+    }
+
+    REPLAYING.with(|replaying| replaying.set(false));
+    Ok(f(report))
+}
+
+// ------ view functions ------
+
+pub fn view_pending_badge<Ms>() -> Node<Ms> {
+    span![
+        class!["tag-default", "tag-pill", "tag-outline"],
+        attrs! {At::Title => "It will be sent when you're back online."},
+        "pending"
+    ]
+}
+
+// ====== PRIVATE ======
+
+fn active_username() -> Option<Username<'static>> {
+    storage::load_viewer().map(|viewer| viewer.username().to_static())
+}
+
+fn send(
+    api: &dyn ConduitApi,
+    viewer: Option<Viewer>,
+    pending_mutation: &PendingMutation,
+) -> ApiFuture<()> {
+    use PendingMutation::*;
+    match pending_mutation {
+        Favorite { slug } => ignore_data(api.favorite(viewer, &Slug::from(slug.clone()))),
+        Unfavorite { slug } => ignore_data(api.unfavorite(viewer, &Slug::from(slug.clone()))),
+        Follow { username } => ignore_data(api.follow(viewer, &Username::from(username.as_str()))),
+        Unfollow { username } => {
+            ignore_data(api.unfollow(viewer, &Username::from(username.as_str())))
+        }
+        CreateComment { slug, body } => {
+            ignore_data(api.create_comment(viewer, &Slug::from(slug.clone()), body.clone()))
+        }
+    }
+}
+
+fn ignore_data<T: 'static>(api_future: ApiFuture<T>) -> ApiFuture<()> {
+    Box::pin(async { api_future.await.map(|_| ()) })
+}
+
+// ====== ====== TESTS ====== ======
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{
+        api::FakeConduit,
+        entity::form::login::{Field, Form},
+    };
+    use std::rc::Rc;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    async fn replay_reports_conflicts_test() {
+        // ====== ARRANGE ======
+        storage::tests::use_in_memory_backend();
+        let fake_conduit = FakeConduit::with_demo_data();
+        let viewer = fake_conduit
+            .login(
+                &Form::new(vec![
+                    Field::Email("demo@example.com".into()),
+                    Field::Password("password".into()),
+                ])
+                .trim_fields()
+                .validate()
+                .unwrap_or_else(|_| panic!("invalid login form")),
+            )
+            .await
+            .unwrap_or_else(|error| panic!("login failed: {:?}", error));
+        let username = viewer.username().to_static();
+        storage::store_pending_mutations(
+            Some(&username),
+            &[
+                PendingMutation::Follow {
+                    username: "jake".into(),
+                },
+                PendingMutation::Favorite {
+                    slug: "deleted-article".into(),
+                },
+            ],
+        );
+        let session = Session::new(Some(viewer), Rc::new(fake_conduit));
+
+        // ====== ACT ======
+        let report = replay(session, |report| report)
+            .await
+            .unwrap_or_else(|report| report);
+
+        // ====== ASSERT ======
+        assert_eq!(report.sent, 1);
+        assert_eq!(report.conflicts.len(), 1);
+        assert!(storage::load_pending_mutations(Some(&username)).is_empty());
+    }
+}
@@ -1,13 +1,16 @@
 use crate::{
-    entity::{ErrorMessage, Username, Viewer},
-    Route,
+    entity::{auth_token, preferences::Theme, ErrorMessage, Username, Viewer},
+    route::HomeQuery,
+    Route, Session,
 };
 use seed::prelude::*;
 use std::borrow::{Borrow, Cow};
+use strum::IntoEnumIterator;
 
 pub mod article;
 pub mod article_editor;
 pub mod blank;
+pub mod drafts;
 pub mod home;
 pub mod login;
 pub mod not_found;
@@ -24,6 +27,15 @@ pub fn scroll_to_top() {
     )
 }
 
+/// Set the theme class of the `body` element; its other classes are kept.
+pub fn apply_theme(theme: Theme) {
+    let class_list = seed::body().class_list();
+    for other_theme in Theme::iter().filter(|other_theme| *other_theme != theme) {
+        let _ = class_list.remove_1(other_theme.class_name());
+    }
+    let _ = class_list.add_1(theme.class_name());
+}
+
 pub fn view_errors<Ms: Clone>(dismiss_errors: Ms, errors: &[ErrorMessage]) -> Node<Ms> {
     if errors.is_empty() {
         empty![]
@@ -66,6 +78,23 @@ impl<'a, Ms> ViewPage<'a, Ms> {
     pub fn into_content(self) -> Node<Ms> {
         self.content
     }
+    pub fn map_msg<OtherMs: 'static>(
+        self,
+        f: impl FnOnce(Ms) -> OtherMs + 'static + Clone,
+    ) -> ViewPage<'a, OtherMs> {
+        ViewPage {
+            title_prefix: self.title_prefix,
+            content: self.content.map_msg(f),
+        }
+    }
+}
+
+// ------ AccountAction ------
+
+/// Action chosen in the account switcher in the header.
+pub enum AccountAction {
+    Switch(Username<'static>),
+    SignOut(Username<'static>),
 }
 
 // ------ Page ------
@@ -78,18 +107,21 @@ pub enum Page<'a> {
     Settings,
     Profile(&'a Username<'a>),
     NewArticle,
+    Drafts,
 }
 
 #[allow(clippy::unused_self)]
 impl<'a> Page<'a> {
     fn is_active(&self, route: &Route) -> bool {
         match (self, route) {
-            (Page::Home, Route::Home)
-            | (Page::Login, Route::Login)
-            | (Page::Register, Route::Register)
+            (Page::Home, Route::Home(_))
+            | (Page::Login, Route::Login(_))
+            | (Page::Register, Route::Register(_))
             | (Page::Settings, Route::Settings)
-            | (Page::NewArticle, Route::NewArticle) => true,
-            (Page::Profile(username), Route::Profile(route_username)) => {
+            | (Page::NewArticle, Route::NewArticle)
+            | (Page::Drafts, Route::Drafts) => true,
+            (Page::Profile(username), Route::Profile(route_username))
+            | (Page::Profile(username), Route::ProfileFavorites(route_username)) => {
                 *username == route_username.borrow()
             }
             _ => false,
@@ -98,11 +130,17 @@ impl<'a> Page<'a> {
 
     // ------ view methods ------
 
-    pub fn view<Ms>(&self, view_page: ViewPage<'a, Ms>, viewer: Option<&Viewer>) -> Vec<Node<Ms>> {
+    pub fn view<Ms: 'static>(
+        &self,
+        view_page: ViewPage<'a, Ms>,
+        session: &Session,
+        on_account_action: fn(AccountAction) -> Ms,
+    ) -> Vec<Node<Ms>> {
         seed::document().set_title(&view_page.title());
 
         vec![
-            self.view_header(viewer),
+            self.view_header(session, on_account_action),
+            self.view_auth_token_warning(session),
             view_page.into_content(),
             self.view_footer(),
         ]
@@ -110,31 +148,69 @@ impl<'a> Page<'a> {
 
     // ====== PRIVATE ======
 
-    fn view_header<Ms>(&self, viewer: Option<&Viewer>) -> Node<Ms> {
+    fn view_header<Ms: 'static>(
+        &self,
+        session: &Session,
+        on_account_action: fn(AccountAction) -> Ms,
+    ) -> Node<Ms> {
         nav![
             class!["navbar", "navbar-light"],
             div![
                 class!["container"],
                 a![
                     class!["navbar-brand"],
-                    attrs! {At::Href => Route::Home.to_string()},
+                    attrs! {At::Href => Route::Home(HomeQuery::default()).to_string()},
                     "conduit"
                 ],
                 ul![
                     class!["nav navbar-nav pull-xs-right"],
-                    self.view_navbar_link(&Route::Home, "Home"),
-                    self.view_menu(viewer),
+                    self.view_navbar_link(&Route::Home(HomeQuery::default()), "Home"),
+                    self.view_menu(session.viewer()),
+                    session
+                        .other_accounts()
+                        .map(|viewer| self.view_account(viewer, on_account_action))
+                        .collect::<Vec<_>>(),
+                    if session.viewer().is_some() {
+                        vec![
+                            self.view_navbar_link(&Route::Login(None), "Add account"),
+                            self.view_navbar_link(&Route::Logout, "Sign out"),
+                        ]
+                    } else {
+                        vec![]
+                    },
                 ],
             ]
         ]
     }
 
+    fn view_auth_token_warning<Ms>(&self, session: &Session) -> Node<Ms> {
+        match session.viewer() {
+            Some(viewer) if auth_token::expires_soon(&viewer.auth_token, js_sys::Date::now()) => {
+                div![
+                    class!["container"],
+                    ul![
+                        class!["error-messages"],
+                        li![
+                            "Your session is about to expire. ",
+                            a![
+                                "Sign in",
+                                attrs! {At::Href => Route::Login(None).to_string()}
+                            ],
+                            " again to stay signed in."
+                        ]
+                    ]
+                ]
+            }
+            _ => empty![],
+        }
+    }
+
     fn view_footer<Ms>(&self) -> Node<Ms> {
         footer![div![
             class!["container"],
             a![
                 class!["logo-font"],
-                attrs! {At::Href => Route::Home.to_string()},
+                attrs! {At::Href => Route::Home(HomeQuery::default()).to_string()},
                 "conduit"
             ],
             span![
@@ -165,14 +241,18 @@ impl<'a> Page<'a> {
     fn view_menu<Ms>(&self, viewer: Option<&Viewer>) -> Vec<Node<Ms>> {
         match viewer {
             None => vec![
-                self.view_navbar_link(&Route::Login, "Sign in"),
-                self.view_navbar_link(&Route::Register, "Sign up"),
+                self.view_navbar_link(&Route::Login(None), "Sign in"),
+                self.view_navbar_link(&Route::Register(None), "Sign up"),
             ],
             Some(viewer) => vec![
                 self.view_navbar_link(
                     &Route::NewArticle,
                     vec![i![class!["ion-compose"]], plain!("\u{00A0}New Post")],
                 ),
+                self.view_navbar_link(
+                    &Route::Drafts,
+                    vec![i![class!["ion-document"]], plain!("\u{00A0}Drafts")],
+                ),
                 self.view_navbar_link(
                     &Route::Settings,
                     vec![i![class!["ion-gear-a"]], plain!("\u{00A0}Settings")],
@@ -187,8 +267,43 @@ impl<'a> Page<'a> {
                         plain!(viewer.username().to_string()),
                     ],
                 ),
-                self.view_navbar_link(&Route::Logout, "Sign out"),
             ],
         }
     }
+
+    /// Click switches to the account, the cross signs it out.
+    fn view_account<Ms: 'static>(
+        &self,
+        viewer: &Viewer,
+        on_account_action: fn(AccountAction) -> Ms,
+    ) -> Node<Ms> {
+        let username = viewer.username().to_static();
+        li![
+            class!["nav-item"],
+            a![
+                class!["nav-link"],
+                style! {"cursor" => "pointer"},
+                attrs! {At::Title => format!("Switch to {}", username.as_str())},
+                raw_ev(Ev::Click, {
+                    let username = username.clone();
+                    move |_| on_account_action(AccountAction::Switch(username))
+                }),
+                img![
+                    class!["user-pic"],
+                    attrs! {At::Src => viewer.avatar().src()}
+                ],
+                plain!(username.to_string()),
+                plain!("\u{00A0}"),
+                i![
+                    class!["ion-close-round"],
+                    attrs! {At::Title => format!("Sign out {}", username.as_str())},
+                    raw_ev(Ev::Click, move |event| {
+                        // Don't switch to the account.
+                        event.stop_propagation();
+                        on_account_action(AccountAction::SignOut(username))
+                    }),
+                ],
+            ]
+        ]
+    }
 }
@@ -1,13 +1,16 @@
 use super::ViewPage;
 use crate::{
+    api,
     entity::{
         author::{self, Author},
-        timestamp, Article, Comment, CommentId, ErrorMessage, Slug,
+        preferences::DateFormat,
+        timestamp, ApiError, Article, Comment, CommentDraft, CommentId, ErrorMessage,
+        PendingMutation, Slug, Viewer,
     },
     helper::take,
-    loading, logger, page, request,
-    route::{self, Route},
-    GMsg, Session,
+    loading, logger, offline, page,
+    route::{self, HomeQuery, Route},
+    storage, GMsg, Session,
 };
 use seed::prelude::*;
 use std::{borrow::Cow, collections::VecDeque};
@@ -21,9 +24,17 @@ use std::{borrow::Cow, collections::VecDeque};
 #[derive(Default)]
 pub struct Model {
     session: Session,
+    /// Slug from the route - known before the article is loaded.
+    slug: Slug,
     errors: Vec<ErrorMessage>,
     comments: Status<(CommentText, VecDeque<Comment>)>,
+    /// Stored comment draft - it's moved into `comments` once they are loaded.
+    comment_draft: String,
     article: Status<Article>,
+    /// Bodies of comments waiting for the connection.
+    pending_comments: Vec<String>,
+    favorite_pending: bool,
+    follow_pending: bool,
 }
 
 impl Model {
@@ -44,6 +55,7 @@ enum Status<T> {
     Loading,
     LoadingSlowly,
     Loaded(T),
+    NotFound,
     Failed,
 }
 
@@ -73,19 +85,27 @@ impl Default for CommentText {
 pub fn init(session: Session, slug: &Slug, orders: &mut impl Orders<Msg, GMsg>) -> Model {
     orders
         .perform_cmd(loading::notify_on_slow_load(Msg::SlowLoadThresholdPassed))
-        .perform_cmd(request::article::load(
-            session.viewer().cloned(),
-            slug,
+        .perform_cmd(api::cmd(
+            session.api().load_article(session.viewer().cloned(), slug),
             Msg::LoadArticleCompleted,
         ))
-        .perform_cmd(request::comment::load_list(
-            session.viewer().cloned(),
-            slug,
+        .perform_cmd(api::cmd(
+            session.api().load_comments(session.viewer().cloned(), slug),
             Msg::LoadCommentsCompleted,
         ));
 
     Model {
+        pending_comments: offline::pending_comments(slug),
+        comment_draft: storage::load_comment_draft(
+            session.viewer().map(Viewer::username),
+            slug,
+            session.preferences().comment_draft_max_age_ms(),
+            js_sys::Date::now(),
+        )
+        .map(|draft| draft.body)
+        .unwrap_or_default(),
         session,
+        slug: slug.clone(),
         ..Model::default()
     }
 }
@@ -97,8 +117,32 @@ pub fn init(session: Session, slug: &Slug, orders: &mut impl Orders<Msg, GMsg>)
 pub fn sink(g_msg: GMsg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>) {
     match g_msg {
         GMsg::SessionChanged(session) => {
-            model.session = session;
-            route::go_to(Route::Home, orders);
+            // Reload the article for the new viewer or the guest (e.g. after switching accounts).
+            let slug = model.slug.clone();
+            *model = init(session, &slug, orders);
+        }
+        GMsg::PendingMutationsReplayed(report) => {
+            model.errors.extend(report.conflict_messages());
+            if let Status::Loaded(article) = &model.article {
+                model.pending_comments = offline::pending_comments(&article.slug);
+                model.favorite_pending = false;
+                model.follow_pending = false;
+                orders
+                    .perform_cmd(api::cmd(
+                        model
+                            .session
+                            .api()
+                            .load_article(model.session.viewer().cloned(), &article.slug),
+                        Msg::LoadArticleCompleted,
+                    ))
+                    .perform_cmd(api::cmd(
+                        model
+                            .session
+                            .api()
+                            .load_comments(model.session.viewer().cloned(), &article.slug),
+                        Msg::LoadCommentsCompleted,
+                    ));
+            }
         }
         _ => (),
     }
@@ -119,13 +163,13 @@ pub enum Msg {
     UnfollowClicked(Author),
     PostCommentClicked(Slug),
     CommentTextEntered(String),
-    LoadArticleCompleted(Result<Article, Vec<ErrorMessage>>),
-    LoadCommentsCompleted(Result<VecDeque<Comment>, Vec<ErrorMessage>>),
-    DeleteArticleCompleted(Result<(), Vec<ErrorMessage>>),
-    DeleteCommentCompleted(Result<CommentId, Vec<ErrorMessage>>),
-    FavoriteChangeCompleted(Result<Article, Vec<ErrorMessage>>),
-    FollowChangeCompleted(Result<Author, Vec<ErrorMessage>>),
-    PostCommentCompleted(Result<Comment, Vec<ErrorMessage>>),
+    LoadArticleCompleted(Result<Article, ApiError>),
+    LoadCommentsCompleted(Result<VecDeque<Comment>, ApiError>),
+    DeleteArticleCompleted(Result<(), ApiError>),
+    DeleteCommentCompleted(Result<CommentId, ApiError>),
+    FavoriteChangeCompleted(Result<Article, ApiError>),
+    FollowChangeCompleted(Result<Author, ApiError>),
+    PostCommentCompleted(Result<Comment, ApiError>),
     SlowLoadThresholdPassed,
 }
 
@@ -134,19 +178,22 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>)
     match msg {
         Msg::DeleteArticleClicked(slug) => {
             orders
-                .perform_cmd(request::article::delete(
-                    model.session.viewer(),
-                    &slug,
+                .perform_cmd(api::cmd(
+                    model
+                        .session
+                        .api()
+                        .delete_article(model.session.viewer(), &slug),
                     Msg::DeleteArticleCompleted,
                 ))
                 .skip();
         }
         Msg::DeleteCommentClicked(slug, comment_id) => {
             orders
-                .perform_cmd(request::comment::delete(
-                    model.session.viewer(),
-                    &slug,
-                    comment_id,
+                .perform_cmd(api::cmd(
+                    model
+                        .session
+                        .api()
+                        .delete_comment(model.session.viewer(), &slug, comment_id),
                     Msg::DeleteCommentCompleted,
                 ))
                 .skip();
@@ -154,38 +201,58 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>)
         Msg::DismissErrorsClicked => {
             model.errors.clear();
         }
+        Msg::FavoriteClicked(slug) if !offline::is_online() => {
+            queue_favorite_change(model, slug, false);
+        }
+        Msg::UnfavoriteClicked(slug) if !offline::is_online() => {
+            queue_favorite_change(model, slug, true);
+        }
+        Msg::FollowClicked(author) if !offline::is_online() => {
+            queue_follow_change(model, &author, true);
+        }
+        Msg::UnfollowClicked(author) if !offline::is_online() => {
+            queue_follow_change(model, &author, false);
+        }
         Msg::FavoriteClicked(slug) => {
             orders
-                .perform_cmd(request::favorite::unfavorite(
-                    model.session.viewer().cloned(),
-                    &slug,
+                .perform_cmd(api::cmd(
+                    model
+                        .session
+                        .api()
+                        .unfavorite(model.session.viewer().cloned(), &slug),
                     Msg::FavoriteChangeCompleted,
                 ))
                 .skip();
         }
         Msg::UnfavoriteClicked(slug) => {
             orders
-                .perform_cmd(request::favorite::favorite(
-                    model.session.viewer().cloned(),
-                    &slug,
+                .perform_cmd(api::cmd(
+                    model
+                        .session
+                        .api()
+                        .favorite(model.session.viewer().cloned(), &slug),
                     Msg::FavoriteChangeCompleted,
                 ))
                 .skip();
         }
         Msg::FollowClicked(author) => {
             orders
-                .perform_cmd(request::follow::follow(
-                    model.session.viewer().cloned(),
-                    author.username(),
+                .perform_cmd(api::cmd(
+                    model
+                        .session
+                        .api()
+                        .follow(model.session.viewer().cloned(), author.username()),
                     Msg::FollowChangeCompleted,
                 ))
                 .skip();
         }
         Msg::UnfollowClicked(author) => {
             orders
-                .perform_cmd(request::follow::unfollow(
-                    model.session.viewer().cloned(),
-                    author.username(),
+                .perform_cmd(api::cmd(
+                    model
+                        .session
+                        .api()
+                        .unfollow(model.session.viewer().cloned(), author.username()),
                     Msg::FollowChangeCompleted,
                 ))
                 .skip();
@@ -196,11 +263,24 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>)
                 Status::Loaded((CommentText::Editing(text), _)) if text.is_empty() => {
                     orders.skip();
                 }
-                Status::Loaded((CommentText::Editing(text), comments)) => {
-                    orders.perform_cmd(request::comment::create(
-                        model.session.viewer().cloned(),
+                Status::Loaded((CommentText::Editing(text), _)) if !offline::is_online() => {
+                    offline::enqueue(PendingMutation::CreateComment {
+                        slug: slug.as_str().to_owned(),
+                        body: text.clone(),
+                    });
+                    storage::delete_comment_draft(
+                        model.session.viewer().map(Viewer::username),
                         &slug,
-                        text.clone(),
+                    );
+                    model.pending_comments.push(take(text));
+                }
+                Status::Loaded((CommentText::Editing(text), comments)) => {
+                    orders.perform_cmd(api::cmd(
+                        model.session.api().create_comment(
+                            model.session.viewer().cloned(),
+                            &slug,
+                            text.clone(),
+                        ),
                         Msg::PostCommentCompleted,
                     ));
                     *model_comments =
@@ -211,31 +291,60 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>)
         }
         Msg::CommentTextEntered(comment_text) => match &mut model.comments {
             Status::Loaded((CommentText::Editing(text), _)) => {
+                if comment_text.trim().is_empty() {
+                    storage::delete_comment_draft(
+                        model.session.viewer().map(Viewer::username),
+                        &model.slug,
+                    );
+                } else {
+                    storage::store_comment_draft(
+                        model.session.viewer().map(Viewer::username),
+                        &CommentDraft::new(&model.slug, comment_text.as_str(), js_sys::Date::now()),
+                    );
+                }
                 *text = comment_text;
             }
             _ => logger::error("Comment text can be changed only in Editing mode!"),
         },
 
-        Msg::LoadArticleCompleted(Ok(article)) => model.article = Status::Loaded(article),
-        Msg::LoadArticleCompleted(Err(errors)) => {
+        Msg::LoadArticleCompleted(Ok(mut article)) => {
+            if let Some(favorited) = offline::pending_favorite(&article.slug) {
+                article.set_favorited(favorited);
+                model.favorite_pending = true;
+            }
+            if let Some(following) = offline::pending_follow(article.author.username()) {
+                article.author.set_following(following);
+                model.follow_pending = true;
+            }
+            model.article = Status::Loaded(article);
+        }
+        Msg::LoadArticleCompleted(Err(ApiError::NotFound)) => {
+            model.article = Status::NotFound;
+        }
+        Msg::LoadArticleCompleted(Err(error)) => {
             model.article = Status::Failed;
-            logger::errors(errors);
+            logger::error(error);
         }
 
         Msg::LoadCommentsCompleted(Ok(comments)) => {
-            model.comments = Status::Loaded((CommentText::Editing("".into()), comments));
+            // Keep the text being written when the comments are reloaded.
+            let comment_text = match &mut model.comments {
+                Status::Loaded((comment_text, _)) => take(comment_text),
+                _ => CommentText::Editing(take(&mut model.comment_draft)),
+            };
+            model.comments = Status::Loaded((comment_text, comments));
         }
-        Msg::LoadCommentsCompleted(Err(errors)) => {
+        Msg::LoadCommentsCompleted(Err(error)) => {
             model.comments = Status::Failed;
-            logger::errors(errors);
+            logger::error(error);
         }
 
         Msg::DeleteArticleCompleted(Ok(())) => {
-            route::go_to(Route::Home, orders);
+            route::go_to(Route::Home(HomeQuery::default()), orders);
         }
-        Msg::DeleteArticleCompleted(Err(errors)) => {
-            logger::errors(&errors);
-            model.errors = errors
+        Msg::DeleteArticleCompleted(Err(error)) => {
+            logger::error(&error);
+            model.errors = error.into_error_messages();
         }
 
         Msg::DeleteCommentCompleted(Ok(comment_id)) => {
@@ -243,17 +352,17 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>)
                 comments.retain(|comment| comment.id != comment_id);
             }
         }
-        Msg::DeleteCommentCompleted(Err(errors)) => {
-            logger::errors(&errors);
-            model.errors = errors
+        Msg::DeleteCommentCompleted(Err(error)) => {
+            logger::error(&error);
+            model.errors = error.into_error_messages();
         }
 
         Msg::FavoriteChangeCompleted(Ok(article)) => {
             model.article = Status::Loaded(article);
         }
-        Msg::FavoriteChangeCompleted(Err(errors)) => {
-            logger::errors(&errors);
-            model.errors = errors
+        Msg::FavoriteChangeCompleted(Err(error)) => {
+            logger::error(&error);
+            model.errors = error.into_error_messages();
         }
 
         Msg::FollowChangeCompleted(Ok(author)) => {
@@ -261,25 +370,29 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>)
                 article.author = author;
             }
         }
-        Msg::FollowChangeCompleted(Err(errors)) => {
-            logger::errors(&errors);
-            model.errors = errors
+        Msg::FollowChangeCompleted(Err(error)) => {
+            logger::error(&error);
+            model.errors = error.into_error_messages();
         }
 
         Msg::PostCommentCompleted(Ok(comment)) => {
+            storage::delete_comment_draft(
+                model.session.viewer().map(Viewer::username),
+                &model.slug,
+            );
             if let Status::Loaded((comment_text, comments)) = &mut model.comments {
                 *comment_text = CommentText::Editing("".into());
                 comments.push_front(comment);
             }
         }
-        Msg::PostCommentCompleted(Err(errors)) => {
+        Msg::PostCommentCompleted(Err(error)) => {
             if let Status::Loaded((comment_text, _)) = &mut model.comments {
                 if let CommentText::Sending(text) = comment_text {
                     *comment_text = CommentText::Editing(take(text))
                 }
             }
-            logger::errors(&errors);
-            model.errors = errors
+            logger::error(&error);
+            model.errors = error.into_error_messages();
         }
 
         Msg::SlowLoadThresholdPassed => {
@@ -293,6 +406,32 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>)
     }
 }
 
+fn queue_favorite_change(model: &mut Model, slug: Slug, favorited: bool) {
+    let slug = slug.as_str().to_owned();
+    offline::enqueue(if favorited {
+        PendingMutation::Favorite { slug }
+    } else {
+        PendingMutation::Unfavorite { slug }
+    });
+    if let Status::Loaded(article) = &mut model.article {
+        article.set_favorited(favorited);
+    }
+    model.favorite_pending = true;
+}
+
+fn queue_follow_change(model: &mut Model, author: &Author, following: bool) {
+    let username = author.username().as_str().to_owned();
+    offline::enqueue(if following {
+        PendingMutation::Follow { username }
+    } else {
+        PendingMutation::Unfollow { username }
+    });
+    if let Status::Loaded(article) = &mut model.article {
+        article.author.set_following(following);
+    }
+    model.follow_pending = true;
+}
+
 // ------ ------
 //     View
 // ------ ------
@@ -306,6 +445,7 @@ pub fn view(model: &Model) -> ViewPage<Msg> {
 fn title_prefix(article: &Status<Article>) -> impl Into<Cow<str>> {
     match &article {
         Status::Loaded(article) => &article.title,
+        Status::NotFound => "Article Not Found",
         _ => "Article",
     }
 }
@@ -314,6 +454,7 @@ fn view_content(model: &Model) -> Node<Msg> {
     match &model.article {
         Status::Loading => empty![],
         Status::LoadingSlowly => loading::view_icon(),
+        Status::NotFound => page::not_found::view().into_content(),
         Status::Failed => loading::view_error("article"),
         Status::Loaded(article) => div![
             class!["article-page"],
@@ -360,7 +501,10 @@ fn view_article_meta(article: &Article, model: &Model) -> Node<Msg> {
         div![
             class!["info"],
             author::view(article.author.username()),
-            span![class!["date"], timestamp::view(&article.created_at)]
+            span![
+                class!["date"],
+                timestamp::view(&article.created_at, model.session.preferences().date_format)
+            ]
         ],
         view_buttons(article, model),
     ]
@@ -372,11 +516,22 @@ fn view_form_and_comments(slug: &Slug, model: &Model) -> Vec<Node<Msg>> {
     match &model.comments {
         Status::Loading => vec![],
         Status::LoadingSlowly => vec![loading::view_icon()],
-        Status::Failed => vec![loading::view_error("comments")],
+        Status::NotFound | Status::Failed => vec![loading::view_error("comments")],
         Status::Loaded((comment_text, comments)) => {
             vec![view_comment_form(slug.clone(), comment_text, model)]
                 .into_iter()
-                .chain(view_comments(slug, comments))
+                .chain(
+                    model
+                        .pending_comments
+                        .iter()
+                        .map(String::as_str)
+                        .map(view_pending_comment),
+                )
+                .chain(view_comments(
+                    slug,
+                    comments,
+                    model.session.preferences().date_format,
+                ))
                 .collect()
         }
     }
@@ -384,12 +539,22 @@ fn view_form_and_comments(slug: &Slug, model: &Model) -> Vec<Node<Msg>> {
 
 fn view_comment_form(slug: Slug, comment_text: &CommentText, model: &Model) -> Node<Msg> {
     match model.session.viewer() {
-        None => p![
-            a!["Sign in", attrs! {At::Href => Route::Login.to_string()}],
-            " or ",
-            a!["Sign up", attrs! {At::Href => Route::Register.to_string()}],
-            " to comment."
-        ],
+        None => {
+            // Come back to the article after signing in.
+            let redirect = || Some(Box::new(Route::Article(slug.clone())));
+            p![
+                a![
+                    "Sign in",
+                    attrs! {At::Href => Route::Login(redirect()).to_string()}
+                ],
+                " or ",
+                a![
+                    "Sign up",
+                    attrs! {At::Href => Route::Register(redirect()).to_string()}
+                ],
+                " to comment."
+            ]
+        }
         Some(viewer) => {
             let (comment_text, post_comment_disabled) = match comment_text {
                 CommentText::Editing(text) => (text, false),
@@ -431,14 +596,18 @@ fn view_comment_form(slug: Slug, comment_text: &CommentText, model: &Model) -> N
     }
 }
 
-fn view_comments(slug: &Slug, comments: &VecDeque<Comment>) -> Vec<Node<Msg>> {
+fn view_comments(
+    slug: &Slug,
+    comments: &VecDeque<Comment>,
+    date_format: DateFormat,
+) -> Vec<Node<Msg>> {
     comments
         .iter()
-        .map(|comment| view_comment(slug, comment))
+        .map(|comment| view_comment(slug, comment, date_format))
         .collect()
 }
 
-fn view_comment(slug: &Slug, comment: &Comment) -> Node<Msg> {
+fn view_comment(slug: &Slug, comment: &Comment, date_format: DateFormat) -> Node<Msg> {
     div![
         class!["card"],
         div![class!["card-block"], p![class!["card-text"], comment.body]],
@@ -458,12 +627,23 @@ fn view_comment(slug: &Slug, comment: &Comment) -> Node<Msg> {
                 attrs! {At::Href => Route::Profile(Cow::Borrowed(comment.author.username())).to_string()},
                 comment.author.username().to_string()
             ],
-            span![class!["date-posted"], timestamp::view(&comment.created_at)],
+            span![
+                class!["date-posted"],
+                timestamp::view(&comment.created_at, date_format)
+            ],
             view_delete_comment_button(slug, comment)
         ]
     ]
 }
 
+fn view_pending_comment(body: &str) -> Node<Msg> {
+    div![
+        class!["card"],
+        div![class!["card-block"], p![class!["card-text"], body]],
+        div![class!["card-footer"], offline::view_pending_badge()]
+    ]
+}
+
 // ------ view buttons ------
 
 fn view_buttons(article: &Article, model: &Model) -> Vec<Node<Msg>> {
@@ -480,17 +660,30 @@ fn view_buttons(article: &Article, model: &Model) -> Vec<Node<Msg>> {
                     Msg::UnfollowClicked(author.clone()),
                     author.username(),
                 ),
+                view_pending_badge(model.follow_pending),
                 plain![" "],
                 view_favorite_button(article),
+                view_pending_badge(model.favorite_pending),
             ],
             author @ Author::NotFollowing(_) => vec![
                 author::view_follow_button(Msg::FollowClicked(author.clone()), author.username()),
+                view_pending_badge(model.follow_pending),
                 plain![" "],
                 view_favorite_button(article),
+                view_pending_badge(model.favorite_pending),
             ],
         },
     }
 }
+
+fn view_pending_badge(pending: bool) -> Node<Msg> {
+    if pending {
+        offline::view_pending_badge()
+    } else {
+        empty![]
+    }
+}
+
 fn view_favorite_button(article: &Article) -> Node<Msg> {
     if article.favorited {
         button![
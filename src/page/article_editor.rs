@@ -1,27 +1,39 @@
 use super::ViewPage;
 use crate::{
+    api,
     entity::{
         form::article_editor::{Field, Form, Problem},
-        Article, Slug,
+        ApiError, Article, ArticleDraft, DraftTarget, Slug, Viewer,
     },
     helper::take,
-    loading, logger, request,
-    route::{self, Route},
-    GMsg, Session,
+    loading, logger,
+    route::{self, HomeQuery, Route},
+    storage, GMsg, Session,
 };
+use gloo_timers::future::TimeoutFuture;
 use seed::prelude::*;
 use std::borrow::Cow;
 
+/// The draft is saved when the user stops typing for this long.
+const DRAFT_SAVE_DELAY_MS: u32 = 1000;
+
 // ------ ------
 //     Model
 // ------ ------
 
 // ------ Model ------
 
-#[derive(Default)]
 pub struct Model {
     session: Session,
     status: Status,
+    /// The form is auto-saved as the draft of this article.
+    draft_target: DraftTarget,
+    /// Draft from the previous visit which the user can restore.
+    restorable_draft: Option<ArticleDraft>,
+    /// Increased on each change so only the last change of a burst saves the draft.
+    draft_revision: u32,
+    /// The draft has been saved by the user and not changed since.
+    draft_saved: bool,
 }
 
 impl Model {
@@ -76,22 +88,54 @@ impl Default for Status {
 
 pub fn init_new(session: Session) -> Model {
     Model {
+        restorable_draft: load_drafts(&session)
+            .into_iter()
+            .find(|draft| matches!(draft.target, DraftTarget::New { .. })),
         session,
-        ..Model::default()
+        status: Status::default(),
+        draft_target: DraftTarget::new_article(),
+        draft_revision: 0,
+        draft_saved: false,
+    }
+}
+
+/// Continue with the draft of a new article; start a new one if the draft doesn't exist.
+pub fn init_draft(session: Session, draft_id: &str) -> Model {
+    match load_drafts(&session)
+        .into_iter()
+        .find(|draft| draft.target.is_new_article(draft_id))
+    {
+        Some(draft) => Model {
+            session,
+            status: Status::EditingNew(Vec::new(), draft.to_form()),
+            draft_target: draft.target,
+            restorable_draft: None,
+            draft_revision: 0,
+            draft_saved: false,
+        },
+        None => init_new(session),
     }
 }
 
 pub fn init_edit(session: Session, slug: Slug, orders: &mut impl Orders<Msg, GMsg>) -> Model {
     orders
         .perform_cmd(loading::notify_on_slow_load(Msg::SlowLoadThresholdPassed))
-        .perform_cmd(request::article::load_for_editor(
-            session.viewer().cloned(),
-            slug.clone(),
+        .perform_cmd(api::cmd(
+            session
+                .api()
+                .load_article_for_editor(session.viewer().cloned(), slug.clone()),
             Msg::ArticleLoadCompleted,
         ));
+    let draft_target = DraftTarget::edit(&slug);
     Model {
+        restorable_draft: session
+            .viewer()
+            .and_then(|viewer| storage::load_article_draft(viewer.username(), &draft_target)),
         session,
         status: Status::Loading(slug),
+        draft_target,
+        draft_revision: 0,
+        draft_saved: false,
     }
 }
 
@@ -102,8 +146,14 @@ pub fn init_edit(session: Session, slug: Slug, orders: &mut impl Orders<Msg, GMs
 pub fn sink(g_msg: GMsg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>) {
     match g_msg {
         GMsg::SessionChanged(session) => {
+            let same_viewer = session.viewer().map(Viewer::username)
+                == model.session.viewer().map(Viewer::username);
+            // Keep the unsaved article when only the viewer's profile has changed (e.g. in another tab).
+            if !same_viewer {
+                save_unsaved_draft(model);
+                route::go_to(Route::Home(HomeQuery::default()), orders);
+            }
             model.session = session;
-            route::go_to(Route::Home, orders);
         }
         _ => (),
     }
@@ -117,10 +167,14 @@ pub fn sink(g_msg: GMsg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>)
 pub enum Msg {
     FieldChanged(Field),
     FormSubmitted,
-    CreateCompleted(Result<Article, Vec<Problem>>),
-    EditCompleted(Result<Article, Vec<Problem>>),
-    ArticleLoadCompleted(Result<Article, (Slug, Vec<Problem>)>),
+    CreateCompleted(Result<Article, ApiError>),
+    EditCompleted(Result<Article, ApiError>),
+    ArticleLoadCompleted(Result<Article, (Slug, ApiError)>),
     SlowLoadThresholdPassed,
+    DraftSaveDelayPassed(u32),
+    RestoreDraftClicked,
+    DiscardDraftClicked,
+    SaveDraftClicked,
 }
 
 #[allow(clippy::match_same_arms)]
@@ -129,65 +183,176 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>)
         Msg::FieldChanged(field) => match &mut model.status {
             Status::Editing(_, _, form) | Status::EditingNew(_, form) => {
                 form.upsert_field(field);
+                model.draft_revision += 1;
+                model.draft_saved = false;
+                orders.perform_cmd(save_draft_after_delay(model.draft_revision));
             }
             _ => logger::error("Can't edit the form, status has to be Editing or EditingNew!"),
         },
-        Msg::FormSubmitted => match &mut model.status {
-            Status::Editing(slug, _, form) => match form.trim_fields().validate() {
-                Ok(valid_form) => {
-                    orders.perform_cmd(request::article::update(
-                        model.session.viewer().cloned(),
-                        &valid_form,
-                        slug,
-                        Msg::EditCompleted,
-                    ));
-                    model.status = Status::Saving(take(slug), take(form));
-                }
-                Err(problems) => {
-                    model.status = Status::Editing(take(slug), problems, take(form));
-                }
-            },
-            Status::EditingNew(_, form) => match form.trim_fields().validate() {
-                Ok(valid_form) => {
-                    orders.perform_cmd(request::article::create(
-                        model.session.viewer().cloned(),
-                        &valid_form,
-                        Msg::CreateCompleted,
-                    ));
-                    model.status = Status::Creating(take(form));
-                }
-                Err(problems) => {
-                    model.status = Status::EditingNew(problems, take(form));
-                }
-            },
-            _ => logger::error("Can't save the form, status has to be Editing or EditingNew!"),
-        },
-        Msg::CreateCompleted(Ok(article)) => route::go_to(Route::Article(article.slug), orders),
-        Msg::CreateCompleted(Err(problems)) => {
+        Msg::FormSubmitted => submit_form(model, orders),
+        Msg::CreateCompleted(Ok(article)) => {
+            delete_draft(model);
+            route::go_to(Route::Article(article.slug), orders)
+        }
+        Msg::CreateCompleted(Err(error)) => {
             if let Status::Creating(form) = &mut model.status {
-                model.status = Status::EditingNew(problems, take(form))
+                model.status = Status::EditingNew(error.into_problems(), take(form))
             }
         }
-        Msg::EditCompleted(Ok(article)) => route::go_to(Route::Article(article.slug), orders),
-        Msg::EditCompleted(Err(problems)) => {
+        Msg::EditCompleted(Ok(article)) => {
+            delete_draft(model);
+            route::go_to(Route::Article(article.slug), orders)
+        }
+        Msg::EditCompleted(Err(error)) => {
             if let Status::Saving(slug, form) = &mut model.status {
-                model.status = Status::Editing(take(slug), problems, take(form))
+                model.status = Status::Editing(take(slug), error.into_problems(), take(form))
             }
         }
         Msg::ArticleLoadCompleted(Ok(article)) => {
             model.status = Status::Editing(article.slug.clone(), vec![], article.into_form());
         }
-        Msg::ArticleLoadCompleted(Err((slug, problems))) => {
-            model.status = Status::LoadingFailed(slug, problems)
+        Msg::ArticleLoadCompleted(Err((slug, error))) => {
+            model.status = Status::LoadingFailed(slug, error.into_problems())
         }
         Msg::SlowLoadThresholdPassed => {
             if let Status::Loading(slug) = &mut model.status {
                 model.status = Status::LoadingSlowly(take(slug));
             }
         }
+        Msg::DraftSaveDelayPassed(revision) => {
+            if revision == model.draft_revision {
+                save_draft(model);
+            }
+        }
+        Msg::RestoreDraftClicked => {
+            if let Some(draft) = model.restorable_draft.take() {
+                match &mut model.status {
+                    Status::Editing(_, _, form) | Status::EditingNew(_, form) => {
+                        *form = draft.to_form();
+                        model.draft_target = draft.target;
+                    }
+                    _ => logger::error(
+                        "Can't restore the draft, status has to be Editing or EditingNew!",
+                    ),
+                }
+            }
+        }
+        Msg::DiscardDraftClicked => {
+            if let (Some(draft), Some(viewer)) =
+                (model.restorable_draft.take(), model.session.viewer())
+            {
+                storage::delete_article_draft(viewer.username(), &draft.target);
+            }
+            // Save changes made while the user was deciding.
+            if model.draft_revision > 0 {
+                save_draft(model);
+            }
+        }
+        Msg::SaveDraftClicked => {
+            // The user prefers the current content to the draft from the previous visit.
+            model.restorable_draft = None;
+            save_draft(model);
+            model.draft_saved = true;
+        }
     }
 }
 
+/// Send the valid form to the server; otherwise show its problems.
+fn submit_form(model: &mut Model, orders: &mut impl Orders<Msg, GMsg>) {
+    match &mut model.status {
+        Status::Editing(slug, _, form) => match form.trim_fields().validate() {
+            Ok(valid_form) => {
+                orders.perform_cmd(api::cmd(
+                    model.session.api().update_article(
+                        model.session.viewer().cloned(),
+                        &valid_form,
+                        slug,
+                    ),
+                    Msg::EditCompleted,
+                ));
+                model.status = Status::Saving(take(slug), take(form));
+            }
+            Err(problems) => {
+                model.status = Status::Editing(take(slug), problems, take(form));
+            }
+        },
+        Status::EditingNew(_, form) => match form.trim_fields().validate() {
+            Ok(valid_form) => {
+                orders.perform_cmd(api::cmd(
+                    model
+                        .session
+                        .api()
+                        .create_article(model.session.viewer().cloned(), &valid_form),
+                    Msg::CreateCompleted,
+                ));
+                model.status = Status::Creating(take(form));
+            }
+            Err(problems) => {
+                model.status = Status::EditingNew(problems, take(form));
+            }
+        },
+        _ => logger::error("Can't save the form, status has to be Editing or EditingNew!"),
+    }
+}
+
+async fn save_draft_after_delay(revision: u32) -> Result<Msg, Msg> {
+    TimeoutFuture::new(DRAFT_SAVE_DELAY_MS).await;
+    Ok(Msg::DraftSaveDelayPassed(revision))
+}
+
+/// Save changes which haven't been auto-saved yet - e.g. before the page is closed
+/// because the session has expired.
+pub fn save_unsaved_draft(model: &mut Model) {
+    if model.draft_revision > 0 && !model.draft_saved {
+        save_draft(model);
+        // Cancel the scheduled save.
+        model.draft_revision += 1;
+    }
+}
+
+fn save_draft(model: &Model) {
+    let viewer = match model.session.viewer() {
+        Some(viewer) => viewer,
+        None => return,
+    };
+    // The draft to restore would be overwritten - wait until the user decides.
+    if model
+        .restorable_draft
+        .as_ref()
+        .is_some_and(|draft| draft.target == model.draft_target)
+    {
+        return;
+    }
+    let form = match &model.status {
+        Status::Editing(_, _, form)
+        | Status::EditingNew(_, form)
+        | Status::Saving(_, form)
+        | Status::Creating(form) => form,
+        _ => return,
+    };
+    let draft = ArticleDraft::new(model.draft_target.clone(), form, js_sys::Date::now());
+    if draft.is_empty() {
+        storage::delete_article_draft(viewer.username(), &draft.target);
+    } else {
+        storage::store_article_draft(viewer.username(), &draft);
+    }
+}
+
+/// The article has been published - cancel scheduled saves and delete the draft.
+fn delete_draft(model: &mut Model) {
+    model.draft_revision += 1;
+    if let Some(viewer) = model.session.viewer() {
+        storage::delete_article_draft(viewer.username(), &model.draft_target);
+    }
+}
+
+fn load_drafts(session: &Session) -> Vec<ArticleDraft> {
+    session
+        .viewer()
+        .map(|viewer| storage::load_article_drafts(viewer.username()))
+        .unwrap_or_default()
+}
+
 // ------ ------
 //     View
 // ------ ------
@@ -220,11 +385,8 @@ fn view_content(model: &Model) -> Node<Msg> {
                 class!["row"],
                 div![
                     class!["col-md-6", "offset-md-3", "col-x32-12"],
-                    if model.session.viewer().is_some() {
-                        view_authenticated(model)
-                    } else {
-                        vec![div!["Sign in to edit this article."]]
-                    }
+                    // Guests are redirected to the login page by `Route::requires_viewer`.
+                    view_authenticated(model)
                 ]
             ]
         ]
@@ -241,18 +403,30 @@ fn view_authenticated(model: &Model) -> Vec<Node<Msg>> {
         Status::Saving(_, form) => vec![view_form(
             form,
             view_save_button(SaveButton::UpdateArticle, true),
+            view_save_draft_button(model.draft_saved, true),
         )],
         Status::Editing(_, problems, form) => vec![
+            view_restorable_draft(model.restorable_draft.as_ref()),
             view_problems(problems),
-            view_form(form, view_save_button(SaveButton::UpdateArticle, false)),
+            view_form(
+                form,
+                view_save_button(SaveButton::UpdateArticle, false),
+                view_save_draft_button(model.draft_saved, false),
+            ),
         ],
         Status::EditingNew(problems, form) => vec![
+            view_restorable_draft(model.restorable_draft.as_ref()),
             view_problems(problems),
-            view_form(form, view_save_button(SaveButton::CreateArticle, false)),
+            view_form(
+                form,
+                view_save_button(SaveButton::CreateArticle, false),
+                view_save_draft_button(model.draft_saved, false),
+            ),
         ],
         Status::Creating(form) => vec![view_form(
             form,
             view_save_button(SaveButton::CreateArticle, true),
+            view_save_draft_button(model.draft_saved, true),
         )],
     }
 }
@@ -269,9 +443,23 @@ fn view_save_button(type_: SaveButton, disabled: bool) -> Node<Msg> {
     ]
 }
 
+fn view_save_draft_button(draft_saved: bool, disabled: bool) -> Node<Msg> {
+    button![
+        class!["btn", "btn-lg", "btn-outline-secondary", "pull-xs-right"],
+        style! {"margin-right" => "10px"},
+        simple_ev(Ev::Click, Msg::SaveDraftClicked),
+        attrs! {At::Type => "button", At::Disabled => (disabled || draft_saved).as_at_value()},
+        if draft_saved {
+            "Draft Saved"
+        } else {
+            "Save Draft"
+        }
+    ]
+}
+
 // ------ view form ------
 
-fn view_form(form: &Form, save_button: Node<Msg>) -> Node<Msg> {
+fn view_form(form: &Form, save_button: Node<Msg>, save_draft_button: Node<Msg>) -> Node<Msg> {
     form![
         raw_ev(Ev::Submit, |event| {
             event.prevent_default();
@@ -279,6 +467,43 @@ fn view_form(form: &Form, save_button: Node<Msg>) -> Node<Msg> {
         }),
         form.iter_fields().map(view_fieldset),
         save_button,
+        save_draft_button,
+    ]
+}
+
+fn view_restorable_draft(draft: Option<&ArticleDraft>) -> Node<Msg> {
+    let draft = match draft {
+        Some(draft) => draft,
+        None => return empty![],
+    };
+    div![
+        class!["form-group"],
+        p![
+            "You have an unsaved draft",
+            match draft.edited_at() {
+                // "February 14, 2018 14:05"
+                Some(edited_at) => format!(" from {}", edited_at.format("%B %-d, %-Y %H:%M")),
+                None => String::new(),
+            },
+            match draft.title.as_str() {
+                "" => String::new(),
+                title => format!(" - \"{}\"", title),
+            },
+            "."
+        ],
+        button![
+            class!["btn", "btn-sm", "btn-outline-primary"],
+            attrs! {At::Type => "button"},
+            simple_ev(Ev::Click, Msg::RestoreDraftClicked),
+            "Restore draft"
+        ],
+        " ",
+        button![
+            class!["btn", "btn-sm", "btn-outline-secondary"],
+            attrs! {At::Type => "button"},
+            simple_ev(Ev::Click, Msg::DiscardDraftClicked),
+            "Discard"
+        ]
     ]
 }
 
@@ -0,0 +1,179 @@
+use super::ViewPage;
+use crate::{
+    entity::{preferences::DateFormat, timestamp, ArticleDraft, DraftTarget, Slug},
+    route::{self, HomeQuery, Route},
+    storage, GMsg, Session,
+};
+use seed::prelude::*;
+
+// ------ ------
+//     Model
+// ------ ------
+
+#[derive(Default)]
+pub struct Model {
+    session: Session,
+    /// Drafts of the active viewer, the most recently edited first.
+    drafts: Vec<ArticleDraft>,
+}
+
+impl Model {
+    pub const fn session(&self) -> &Session {
+        &self.session
+    }
+}
+
+impl From<Model> for Session {
+    fn from(model: Model) -> Self {
+        model.session
+    }
+}
+
+// ------ ------
+//     Init
+// ------ ------
+
+pub fn init(session: Session) -> Model {
+    Model {
+        drafts: session
+            .viewer()
+            .map(|viewer| storage::load_article_drafts(viewer.username()))
+            .unwrap_or_default(),
+        session,
+    }
+}
+
+// ------ ------
+//     Sink
+// ------ ------
+
+pub fn sink(g_msg: GMsg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>) {
+    match g_msg {
+        GMsg::SessionChanged(session) => {
+            if session.viewer().is_some() {
+                // Drafts belong to the active viewer.
+                *model = init(session);
+            } else {
+                model.session = session;
+                route::go_to(Route::Home(HomeQuery::default()), orders);
+            }
+        }
+        _ => (),
+    }
+}
+
+// ------ ------
+//    Update
+// ------ ------
+
+#[derive(Clone)]
+pub enum Msg {
+    DiscardClicked(DraftTarget),
+}
+
+pub fn update(msg: Msg, model: &mut Model, _: &mut impl Orders<Msg, GMsg>) {
+    match msg {
+        Msg::DiscardClicked(target) => {
+            if let Some(viewer) = model.session.viewer() {
+                storage::delete_article_draft(viewer.username(), &target);
+            }
+            model.drafts.retain(|draft| draft.target != target);
+        }
+    }
+}
+
+// ------ ------
+//     View
+// ------ ------
+
+pub fn view<'a>(model: &Model) -> ViewPage<'a, Msg> {
+    ViewPage::new("Drafts", view_content(model))
+}
+
+// ====== PRIVATE ======
+
+fn view_content(model: &Model) -> Node<Msg> {
+    div![
+        class!["container", "page"],
+        div![
+            class!["row"],
+            div![
+                class!["col-md-10", "offset-md-1", "col-xs-12"],
+                h1!["Drafts"],
+                if model.drafts.is_empty() {
+                    div!["No drafts are here... yet."]
+                } else {
+                    div![model
+                        .drafts
+                        .iter()
+                        .map(|draft| view_draft(draft, model.session.preferences().date_format))]
+                }
+            ]
+        ]
+    ]
+}
+
+fn view_draft(draft: &ArticleDraft, date_format: DateFormat) -> Node<Msg> {
+    div![
+        class!["article-preview"],
+        h1![if draft.title.trim().is_empty() {
+            "Untitled"
+        } else {
+            draft.title.as_str()
+        }],
+        p![
+            view_target(&draft.target),
+            match draft.edited_at() {
+                Some(edited_at) => vec![
+                    span![" - last edited "],
+                    timestamp::view(&edited_at, date_format)
+                ],
+                None => Vec::new(),
+            }
+        ],
+        a![
+            class!["btn", "btn-sm", "btn-outline-primary"],
+            attrs! {At::Href => editor_route(&draft.target).to_string()},
+            "Open in editor"
+        ],
+        " ",
+        a![
+            class!["btn", "btn-sm", "btn-outline-secondary"],
+            attrs! {
+                At::Href => format!(
+                    "data:text/markdown;charset=utf-8,{}",
+                    js_sys::encode_uri_component(&draft.to_markdown())
+                ),
+                At::Download => draft.file_name(),
+            },
+            "Export"
+        ],
+        " ",
+        button![
+            class!["btn", "btn-sm", "btn-outline-danger"],
+            simple_ev(Ev::Click, Msg::DiscardClicked(draft.target.clone())),
+            "Discard"
+        ]
+    ]
+}
+
+fn view_target(target: &DraftTarget) -> Node<Msg> {
+    match target {
+        DraftTarget::New { .. } => plain!["New article"],
+        DraftTarget::Edit { slug } => span![
+            "Edit of ",
+            a![
+                attrs! {At::Href => Route::Article(Slug::from(slug.clone())).to_string()},
+                slug
+            ]
+        ],
+    }
+}
+
+/// The editor offers to restore drafts of existing articles.
+fn editor_route(target: &DraftTarget) -> Route<'static> {
+    match target {
+        DraftTarget::New { id } => Route::Draft(id.to_string()),
+        DraftTarget::Edit { slug } => Route::EditArticle(Slug::from(slug.clone())),
+    }
+}
@@ -1,20 +1,37 @@
 use super::ViewPage;
 use crate::{
+    api,
     entity::{
         article::{self, Article},
-        ErrorMessage, PageNumber, PaginatedList, Tag, Viewer,
+        preferences::HomeFeed,
+        ApiError, PageNumber, PaginatedList, Tag, Viewer,
     },
-    loading, logger, page, request, GMsg, Session,
+    loading, logger, page, request,
+    route::{self, HomeQuery, Route},
+    GMsg, Session,
 };
 use seed::prelude::*;
-use std::future::Future;
+use std::{convert::TryInto, future::Future};
 
 fn fetch_feed(
-    viewer: Option<Viewer>,
+    request_id: article::feed::RequestId,
+    session: &Session,
     selected_feed: &SelectedFeed,
     page_number: PageNumber,
 ) -> impl Future<Output = Result<Msg, Msg>> {
-    request::feed::load_for_home(viewer, selected_feed, page_number, Msg::FeedLoadCompleted)
+    let per_page = session
+        .preferences()
+        .articles_per_page
+        .unwrap_or(*request::feed::HOME_ARTICLES_PER_PAGE);
+    let feed = session.api().load_home_feed(
+        session.viewer().cloned(),
+        selected_feed,
+        page_number,
+        per_page,
+    );
+    api::cmd(feed, move |result| {
+        Msg::FeedLoadCompleted(request_id, result)
+    })
 }
 
 // ------ ------
@@ -28,6 +45,7 @@ pub struct Model {
     session: Session,
     selected_feed: SelectedFeed,
     feed_page: PageNumber,
+    feed_request_id: article::feed::RequestId,
     tags: Status<Vec<Tag>>,
     feed: Status<article::feed::Model>,
 }
@@ -36,6 +54,31 @@ impl Model {
     pub const fn session(&self) -> &Session {
         &self.session
     }
+
+    /// Fetch the feed for the current selection; responses to previous fetches will be ignored.
+    fn fetch_feed(&mut self) -> impl Future<Output = Result<Msg, Msg>> {
+        self.feed_request_id = self.feed_request_id.next();
+        fetch_feed(
+            self.feed_request_id,
+            &self.session,
+            &self.selected_feed,
+            self.feed_page,
+        )
+    }
+
+    /// The route which restores the current feed selection.
+    fn route(&self) -> Route<'static> {
+        let (feed, tag) = match &self.selected_feed {
+            SelectedFeed::Your(_) => (Some(HomeFeed::Your), None),
+            SelectedFeed::Global => (Some(HomeFeed::Global), None),
+            SelectedFeed::Tag(tag) => (None, Some(tag.clone())),
+        };
+        Route::Home(HomeQuery {
+            feed,
+            tag,
+            page: self.feed_page,
+        })
+    }
 }
 
 impl From<Model> for Session {
@@ -78,24 +121,28 @@ impl<'a> Default for SelectedFeed {
 //     Init
 // ------ ------
 
-pub fn init(session: Session, orders: &mut impl Orders<Msg, GMsg>) -> Model {
-    let selected_feed = session
-        .viewer()
-        .cloned()
-        .map_or_else(SelectedFeed::default, SelectedFeed::Your);
+pub fn init(session: Session, query: HomeQuery, orders: &mut impl Orders<Msg, GMsg>) -> Model {
+    let home_feed = query.feed.unwrap_or(session.preferences().home_feed);
+    let selected_feed = match (query.tag, session.viewer(), home_feed) {
+        (Some(tag), _, _) => SelectedFeed::Tag(tag),
+        (None, Some(viewer), HomeFeed::Your) => SelectedFeed::Your(viewer.clone()),
+        _ => SelectedFeed::default(),
+    };
 
     orders
         .perform_cmd(loading::notify_on_slow_load(Msg::SlowLoadThresholdPassed))
-        .perform_cmd(request::tag::load_list(Msg::TagsLoadCompleted))
+        .perform_cmd(api::cmd(session.api().load_tags(), Msg::TagsLoadCompleted))
         .perform_cmd(fetch_feed(
-            session.viewer().cloned(),
+            article::feed::RequestId::default(),
+            &session,
             &selected_feed,
-            PageNumber::default(),
+            query.page,
         ));
 
     Model {
         session,
         selected_feed,
+        feed_page: query.page,
         ..Model::default()
     }
 }
@@ -104,10 +151,21 @@ pub fn init(session: Session, orders: &mut impl Orders<Msg, GMsg>) -> Model {
 //     Sink
 // ------ ------
 
-pub fn sink(g_msg: GMsg, model: &mut Model) {
+pub fn sink(g_msg: GMsg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>) {
     match g_msg {
+        // Reload feeds for the new viewer (e.g. after switching accounts).
         GMsg::SessionChanged(session) => {
-            model.session = session;
+            // Keep the selection from the URL; the rest depends on the new viewer.
+            let query = match seed::browser::url::current().try_into() {
+                Ok(Route::Home(query)) => query,
+                _ => HomeQuery::default(),
+            };
+            *model = init(session, query, orders);
+        }
+        GMsg::PendingMutationsReplayed(report) => {
+            if let Status::Loaded(feed_model) = &mut model.feed {
+                feed_model.pending_mutations_replayed(&report);
+            }
         }
         _ => (),
     }
@@ -123,8 +181,11 @@ pub enum Msg {
     TagClicked(Tag),
     TabClicked(SelectedFeed),
     FeedPageClicked(PageNumber),
-    FeedLoadCompleted(Result<PaginatedList<Article>, Vec<ErrorMessage>>),
-    TagsLoadCompleted(Result<Vec<Tag>, Vec<ErrorMessage>>),
+    FeedLoadCompleted(
+        article::feed::RequestId,
+        Result<PaginatedList<Article>, ApiError>,
+    ),
+    TagsLoadCompleted(Result<Vec<Tag>, ApiError>),
     FeedMsg(article::feed::Msg),
     SlowLoadThresholdPassed,
 }
@@ -134,43 +195,37 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>)
         Msg::TagClicked(tag) => {
             model.selected_feed = SelectedFeed::Tag(tag);
             model.feed_page = PageNumber::default();
-            orders.perform_cmd(fetch_feed(
-                model.session.viewer().cloned(),
-                &model.selected_feed,
-                model.feed_page,
-            ));
+            route::push(model.route());
+            orders.perform_cmd(model.fetch_feed());
         }
         Msg::TabClicked(selected_feed) => {
             model.selected_feed = selected_feed;
             model.feed_page = PageNumber::default();
-            orders.perform_cmd(fetch_feed(
-                model.session.viewer().cloned(),
-                &model.selected_feed,
-                model.feed_page,
-            ));
+            route::push(model.route());
+            orders.perform_cmd(model.fetch_feed());
         }
         Msg::FeedPageClicked(page_number) => {
             model.feed_page = page_number;
-            orders.perform_cmd(fetch_feed(
-                model.session.viewer().cloned(),
-                &model.selected_feed,
-                model.feed_page,
-            ));
+            route::push(model.route());
+            orders.perform_cmd(model.fetch_feed());
             page::scroll_to_top()
         }
-        Msg::FeedLoadCompleted(Ok(paginated_list)) => {
+        Msg::FeedLoadCompleted(request_id, _) if request_id != model.feed_request_id => {
+            orders.skip();
+        }
+        Msg::FeedLoadCompleted(_, Ok(paginated_list)) => {
             model.feed = Status::Loaded(article::feed::init(model.session.clone(), paginated_list));
         }
-        Msg::FeedLoadCompleted(Err(errors)) => {
+        Msg::FeedLoadCompleted(_, Err(error)) => {
             model.feed = Status::Failed;
-            logger::errors(errors);
+            logger::error(error);
         }
         Msg::TagsLoadCompleted(Ok(tags)) => {
             model.tags = Status::Loaded(tags);
         }
-        Msg::TagsLoadCompleted(Err(errors)) => {
+        Msg::TagsLoadCompleted(Err(error)) => {
             model.tags = Status::Failed;
-            logger::errors(errors);
+            logger::error(error);
         }
         Msg::FeedMsg(feed_msg) => match &mut model.feed {
             Status::Loaded(feed_model) => {
@@ -1,12 +1,12 @@
 use super::ViewPage;
 use crate::{
+    api,
     entity::{
         form::login::{Field, Form, Problem},
-        Viewer,
+        ApiError, Viewer,
     },
-    request,
-    route::{self, Route},
-    GMsg, Session,
+    route::{self, HomeQuery, Route},
+    storage, GMsg, Session,
 };
 use seed::prelude::*;
 
@@ -19,6 +19,10 @@ pub struct Model {
     session: Session,
     problems: Vec<Problem>,
     form: Form,
+    /// Keep the viewer signed in after the browser is closed.
+    remember_me: bool,
+    /// Opened after the viewer signs in; `None` - the home page.
+    redirect: Option<Route<'static>>,
 }
 
 impl Model {
@@ -37,9 +41,24 @@ impl From<Model> for Session {
 //     Init
 // ------ ------
 
-pub fn init(session: Session) -> Model {
+pub fn init(session: Session, redirect: Option<Route<'static>>) -> Model {
     Model {
         session,
+        redirect,
+        remember_me: storage::remember_me(),
+        ..Model::default()
+    }
+}
+
+/// Ask the user to sign in again and send them back to `return_route` afterwards.
+pub fn init_after_session_expired(session: Session, return_route: Option<Route<'static>>) -> Model {
+    Model {
+        session,
+        problems: vec![Problem::new_server_error(
+            "Your session has expired. Please sign in again.",
+        )],
+        redirect: return_route,
+        remember_me: storage::remember_me(),
         ..Model::default()
     }
 }
@@ -52,7 +71,13 @@ pub fn sink(g_msg: GMsg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>)
     match g_msg {
         GMsg::SessionChanged(session) => {
             model.session = session;
-            route::go_to(Route::Home, orders);
+            if model.session.viewer().is_some() {
+                let route = model
+                    .redirect
+                    .take()
+                    .unwrap_or(Route::Home(HomeQuery::default()));
+                route::go_to(route, orders);
+            }
         }
         _ => (),
     }
@@ -65,7 +90,8 @@ pub fn sink(g_msg: GMsg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>)
 pub enum Msg {
     FormSubmitted,
     FieldChanged(Field),
-    LoginCompleted(Result<Viewer, Vec<Problem>>),
+    RememberMeToggled,
+    LoginCompleted(Result<Viewer, ApiError>),
 }
 
 pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>) {
@@ -73,7 +99,10 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>)
         Msg::FormSubmitted => match model.form.trim_fields().validate() {
             Ok(valid_form) => {
                 model.problems.clear();
-                orders.perform_cmd(request::login::login(&valid_form, Msg::LoginCompleted));
+                orders.perform_cmd(api::cmd(
+                    model.session.api().login(&valid_form),
+                    Msg::LoginCompleted,
+                ));
             }
             Err(problems) => {
                 model.problems = problems;
@@ -82,12 +111,15 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>)
         Msg::FieldChanged(field) => {
             model.form.upsert_field(field);
         }
+        Msg::RememberMeToggled => {
+            model.remember_me = !model.remember_me;
+        }
         Msg::LoginCompleted(Ok(viewer)) => {
-            viewer.store();
-            orders.send_g_msg(GMsg::SessionChanged(Session::LoggedIn(viewer)));
+            storage::store_signed_in_viewer(&viewer, model.remember_me);
+            orders.send_g_msg(GMsg::SessionChanged(model.session.logged_in(viewer)));
         }
-        Msg::LoginCompleted(Err(problems)) => {
-            model.problems = problems;
+        Msg::LoginCompleted(Err(error)) => {
+            model.problems = error.into_problems();
         }
     }
 }
@@ -115,7 +147,10 @@ fn view_content(model: &Model) -> Node<Msg> {
                     p![
                         class!["text-xs-center"],
                         a![
-                            attrs! {At::Href => Route::Register.to_string()},
+                            attrs! {
+                                At::Href => Route::Register(model.redirect.clone().map(Box::new))
+                                    .to_string()
+                            },
                             "Need an account?"
                         ]
                     ],
@@ -123,7 +158,7 @@ fn view_content(model: &Model) -> Node<Msg> {
                         class!["error-messages"],
                         model.problems.iter().map(|problem| li![problem.message()])
                     ],
-                    view_form(&model.form)
+                    view_form(&model.form, model.remember_me)
                 ]
             ]
         ]
@@ -132,13 +167,14 @@ fn view_content(model: &Model) -> Node<Msg> {
 
 // ------ view form ------
 
-fn view_form(form: &Form) -> Node<Msg> {
+fn view_form(form: &Form, remember_me: bool) -> Node<Msg> {
     form![
         raw_ev(Ev::Submit, |event| {
             event.prevent_default();
             Msg::FormSubmitted
         }),
         form.iter_fields().map(view_fieldset),
+        view_remember_me(remember_me),
         button![
             class!["btn", "btn-lg", "btn-primary", "pull-xs-right"],
             "Sign in"
@@ -178,3 +214,94 @@ fn view_fieldset(field: &Field) -> Node<Msg> {
         ],
     }
 }
+
+fn view_remember_me(remember_me: bool) -> Node<Msg> {
+    fieldset![
+        class!["form-group"],
+        label![
+            input![
+                attrs! {
+                    At::Type => "checkbox",
+                    At::Checked => remember_me.as_at_value()
+                },
+                raw_ev(Ev::Change, |_| Msg::RememberMeToggled),
+            ],
+            " Remember me"
+        ]
+    ]
+}
+
+// ====== ====== TESTS ====== ======
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{api::FakeConduit, helper::tests::TestOrders, storage};
+    use std::rc::Rc;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    async fn login_with_fake_conduit_test() {
+        // ====== ARRANGE ======
+        storage::tests::use_in_memory_backend();
+        let session = Session::new(None, Rc::new(FakeConduit::with_demo_data()));
+        let mut model = init(session, None);
+        let mut orders = TestOrders::default();
+        update(
+            Msg::FieldChanged(Field::Email("demo@example.com".into())),
+            &mut model,
+            &mut orders,
+        );
+        update(
+            Msg::FieldChanged(Field::Password("password".into())),
+            &mut model,
+            &mut orders,
+        );
+
+        // ====== ACT ======
+        update(Msg::FormSubmitted, &mut model, &mut orders);
+        for msg in orders.run_cmds().await {
+            update(msg, &mut model, &mut orders);
+        }
+
+        // ====== ASSERT ======
+        assert!(model.problems.is_empty());
+        let viewer = storage::load_viewer().expect("viewer not stored");
+        assert!(matches!(
+            orders.g_msgs.as_slice(),
+            [GMsg::SessionChanged(session)] if session.viewer() == Some(&viewer)
+        ));
+    }
+
+    #[wasm_bindgen_test]
+    async fn login_with_wrong_password_test() {
+        // ====== ARRANGE ======
+        storage::tests::use_in_memory_backend();
+        let session = Session::new(None, Rc::new(FakeConduit::with_demo_data()));
+        let mut model = init(session, None);
+        let mut orders = TestOrders::default();
+        update(
+            Msg::FieldChanged(Field::Email("demo@example.com".into())),
+            &mut model,
+            &mut orders,
+        );
+        update(
+            Msg::FieldChanged(Field::Password("wrong password".into())),
+            &mut model,
+            &mut orders,
+        );
+
+        // ====== ACT ======
+        update(Msg::FormSubmitted, &mut model, &mut orders);
+        for msg in orders.run_cmds().await {
+            update(msg, &mut model, &mut orders);
+        }
+
+        // ====== ASSERT ======
+        assert!(!model.problems.is_empty());
+        assert!(storage::load_viewer().is_none());
+        assert!(orders.g_msgs.is_empty());
+    }
+}
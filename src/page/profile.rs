@@ -1,16 +1,17 @@
 use super::ViewPage;
-use std::borrow::Cow;
+use std::{borrow::Cow, future::Future};
 
 use seed::prelude::*;
 
 use crate::{
+    api,
     entity::{
         article::{self, Article},
         author::{self, Author},
-        ErrorMessage, PageNumber, PaginatedList, Username, Viewer,
+        ApiError, ErrorMessage, PageNumber, PaginatedList, PendingMutation, Username, Viewer,
     },
     helper::take,
-    loading, logger, page, request,
+    loading, logger, offline, page, request,
     route::{self, Route},
     GMsg, Session,
 };
@@ -18,20 +19,26 @@ use crate::{
 static DEFAULT_TITLE_PREFIX: &str = "Profile";
 static TITLE_PREFIX_FOR_ME: &str = "My Profile";
 
-async fn fetch_feed(
-    viewer: Option<Viewer>,
+fn fetch_feed(
+    request_id: article::feed::RequestId,
+    session: &Session,
     username: Username<'static>,
     selected_feed: SelectedFeed,
     page_number: PageNumber,
-) -> Result<Msg, Msg> {
-    request::feed::load_for_profile(
-        viewer,
+) -> impl Future<Output = Result<Msg, Msg>> {
+    let feed = session.api().load_profile_feed(
+        session.viewer().cloned(),
         username,
         selected_feed,
         page_number,
-        Msg::FeedLoadCompleted,
-    )
-    .await
+        session
+            .preferences()
+            .articles_per_page
+            .unwrap_or(*request::feed::PROFILE_ARTICLES_PER_PAGE),
+    );
+    api::cmd(feed, move |result| {
+        Msg::FeedLoadCompleted(request_id, result)
+    })
 }
 
 // ------ ------
@@ -46,7 +53,10 @@ pub struct Model<'a> {
     errors: Vec<ErrorMessage>,
     selected_feed: SelectedFeed,
     feed_page: PageNumber,
+    feed_request_id: article::feed::RequestId,
     author: Status<'a, Author>,
+    /// Follow change of the author is waiting for the connection.
+    follow_pending: bool,
     feed: Status<'a, article::feed::Model>,
 }
 
@@ -54,6 +64,27 @@ impl<'a> Model<'a> {
     pub const fn session(&self) -> &Session {
         &self.session
     }
+
+    /// Fetch the feed for the current selection; responses to previous fetches will be ignored.
+    fn fetch_feed(&mut self) -> impl Future<Output = Result<Msg, Msg>> {
+        self.feed_request_id = self.feed_request_id.next();
+        fetch_feed(
+            self.feed_request_id,
+            &self.session,
+            self.author.username().to_static(),
+            self.selected_feed,
+            self.feed_page,
+        )
+    }
+
+    /// The route which opens the selected tab.
+    fn route(&self) -> Route<'static> {
+        let username = Cow::Owned(self.author.username().to_static());
+        match self.selected_feed {
+            SelectedFeed::MyArticles => Route::Profile(username),
+            SelectedFeed::FavoritedArticles => Route::ProfileFavorites(username),
+        }
+    }
 }
 
 impl<'a> From<Model<'a>> for Session {
@@ -109,24 +140,28 @@ impl Default for SelectedFeed {
 pub fn init<'a>(
     session: Session,
     username: Username<'static>,
+    selected_feed: SelectedFeed,
     orders: &mut impl Orders<Msg, GMsg>,
 ) -> Model<'a> {
     orders
         .perform_cmd(loading::notify_on_slow_load(Msg::SlowLoadThresholdPassed))
-        .perform_cmd(request::author::load(
-            session.viewer().cloned(),
-            username.clone(),
+        .perform_cmd(api::cmd(
+            session
+                .api()
+                .load_author(session.viewer().cloned(), username.clone()),
             Msg::AuthorLoadCompleted,
         ))
         .perform_cmd(fetch_feed(
-            session.viewer().cloned(),
+            article::feed::RequestId::default(),
+            &session,
             username.clone(),
-            SelectedFeed::default(),
+            selected_feed,
             PageNumber::default(),
         ));
 
     Model {
         session,
+        selected_feed,
         author: Status::Loading(username.clone()),
         feed: Status::Loading(username),
         ..Model::default()
@@ -140,8 +175,16 @@ pub fn init<'a>(
 pub fn sink(g_msg: GMsg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>) {
     match g_msg {
         GMsg::SessionChanged(session) => {
-            model.session = session;
-            route::go_to(Route::Home, orders);
+            // Reload the profile for the new viewer or the guest (e.g. after switching accounts).
+            let username = model.author.username().to_static();
+            *model = init(session, username, model.selected_feed, orders);
+        }
+        GMsg::PendingMutationsReplayed(report) => {
+            model.follow_pending = false;
+            model.errors.extend(report.conflict_messages());
+            if let Status::Loaded(feed_model) = &mut model.feed {
+                feed_model.pending_mutations_replayed(&report);
+            }
         }
         _ => (),
     }
@@ -159,9 +202,12 @@ pub enum Msg {
     UnfollowClicked,
     TabClicked(SelectedFeed),
     FeedPageClicked(PageNumber),
-    FollowChangeCompleted(Result<Author, Vec<ErrorMessage>>),
-    AuthorLoadCompleted(Result<Author, (Username<'static>, Vec<ErrorMessage>)>),
-    FeedLoadCompleted(Result<PaginatedList<Article>, (Username<'static>, Vec<ErrorMessage>)>),
+    FollowChangeCompleted(Result<Author, ApiError>),
+    AuthorLoadCompleted(Result<Author, (Username<'static>, ApiError)>),
+    FeedLoadCompleted(
+        article::feed::RequestId,
+        Result<PaginatedList<Article>, (Username<'static>, ApiError)>,
+    ),
     FeedMsg(article::feed::Msg),
     SlowLoadThresholdPassed,
 }
@@ -172,20 +218,30 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>)
         Msg::DismissErrorsClicked => {
             model.errors.clear();
         }
+        Msg::FollowClicked if !offline::is_online() => {
+            queue_follow_change(model, true);
+        }
+        Msg::UnfollowClicked if !offline::is_online() => {
+            queue_follow_change(model, false);
+        }
         Msg::FollowClicked => {
             orders
-                .perform_cmd(request::follow::follow(
-                    model.session.viewer().cloned(),
-                    model.author.username(),
+                .perform_cmd(api::cmd(
+                    model
+                        .session
+                        .api()
+                        .follow(model.session.viewer().cloned(), model.author.username()),
                     Msg::FollowChangeCompleted,
                 ))
                 .skip();
         }
         Msg::UnfollowClicked => {
             orders
-                .perform_cmd(request::follow::unfollow(
-                    model.session.viewer().cloned(),
-                    model.author.username(),
+                .perform_cmd(api::cmd(
+                    model
+                        .session
+                        .api()
+                        .unfollow(model.session.viewer().cloned(), model.author.username()),
                     Msg::FollowChangeCompleted,
                 ))
                 .skip();
@@ -193,41 +249,41 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>)
         Msg::TabClicked(selected_feed) => {
             model.selected_feed = selected_feed;
             model.feed_page = PageNumber::default();
-            orders.perform_cmd(fetch_feed(
-                model.session.viewer().cloned(),
-                model.author.username().to_static(),
-                selected_feed,
-                model.feed_page,
-            ));
+            route::push(model.route());
+            orders.perform_cmd(model.fetch_feed());
         }
         Msg::FeedPageClicked(page_number) => {
             model.feed_page = page_number;
-            orders.perform_cmd(fetch_feed(
-                model.session.viewer().cloned(),
-                model.author.username().to_static(),
-                model.selected_feed,
-                model.feed_page,
-            ));
+            orders.perform_cmd(model.fetch_feed());
             page::scroll_to_top();
         }
         Msg::FollowChangeCompleted(Ok(author)) => model.author = Status::Loaded(author),
-        Msg::FollowChangeCompleted(Err(errors)) => {
-            logger::errors(&errors);
-            model.errors = errors;
+        Msg::FollowChangeCompleted(Err(error)) => {
+            logger::error(&error);
+            model.errors = error.into_error_messages();
         }
-        Msg::AuthorLoadCompleted(Ok(author)) => model.author = Status::Loaded(author),
-        Msg::AuthorLoadCompleted(Err((username, errors))) => {
+        Msg::AuthorLoadCompleted(Ok(mut author)) => {
+            if let Some(following) = offline::pending_follow(author.username()) {
+                author.set_following(following);
+                model.follow_pending = true;
+            }
+            model.author = Status::Loaded(author);
+        }
+        Msg::AuthorLoadCompleted(Err((username, error))) => {
             model.author = Status::Failed(username);
-            logger::errors(&errors);
-            model.errors = errors;
+            logger::error(&error);
+            model.errors = error.into_error_messages();
         }
-        Msg::FeedLoadCompleted(Ok(paginated_list)) => {
+        Msg::FeedLoadCompleted(request_id, _) if request_id != model.feed_request_id => {
+            orders.skip();
+        }
+        Msg::FeedLoadCompleted(_, Ok(paginated_list)) => {
             model.feed = Status::Loaded(article::feed::init(model.session.clone(), paginated_list));
         }
-        Msg::FeedLoadCompleted(Err((username, errors))) => {
+        Msg::FeedLoadCompleted(_, Err((username, error))) => {
             model.feed = Status::Failed(username);
-            logger::errors(&errors);
-            model.errors = errors;
+            logger::error(&error);
+            model.errors = error.into_error_messages();
         }
         Msg::FeedMsg(feed_msg) => match &mut model.feed {
             Status::Loaded(feed_model) => {
@@ -243,6 +299,19 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>)
     }
 }
 
+fn queue_follow_change(model: &mut Model, following: bool) {
+    if let Status::Loaded(author) = &mut model.author {
+        let username = author.username().as_str().to_owned();
+        offline::enqueue(if following {
+            PendingMutation::Follow { username }
+        } else {
+            PendingMutation::Unfollow { username }
+        });
+        author.set_following(following);
+        model.follow_pending = true;
+    }
+}
+
 // ------ ------
 //     View
 // ------ ------
@@ -317,16 +386,26 @@ fn view_follow_button(author: &Author, model: &Model) -> Node<Msg> {
         None => empty![],
         Some(_) => match author {
             Author::IsViewer(..) => empty![],
-            Author::Following(_) => {
-                author::view_unfollow_button(Msg::UnfollowClicked, author.username())
-            }
-            Author::NotFollowing(_) => {
-                author::view_follow_button(Msg::FollowClicked, author.username())
-            }
+            Author::Following(_) => div![
+                author::view_unfollow_button(Msg::UnfollowClicked, author.username()),
+                view_follow_pending_badge(model),
+            ],
+            Author::NotFollowing(_) => div![
+                author::view_follow_button(Msg::FollowClicked, author.username()),
+                view_follow_pending_badge(model),
+            ],
         },
     }
 }
 
+fn view_follow_pending_badge(model: &Model) -> Node<Msg> {
+    if model.follow_pending {
+        offline::view_pending_badge()
+    } else {
+        empty![]
+    }
+}
+
 // ------ view feed ------
 
 fn view_feed(model: &Model) -> Node<Msg> {
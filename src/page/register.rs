@@ -1,12 +1,12 @@
 use super::ViewPage;
 use crate::{
+    api,
     entity::{
         form::register::{Field, Form, Problem},
-        Viewer,
+        ApiError, Viewer,
     },
-    request,
-    route::{self, Route},
-    GMsg, Session,
+    route::{self, HomeQuery, Route},
+    storage, GMsg, Session,
 };
 use seed::prelude::*;
 
@@ -19,6 +19,10 @@ pub struct Model {
     session: Session,
     problems: Vec<Problem>,
     form: Form,
+    /// Keep the viewer signed in after the browser is closed.
+    remember_me: bool,
+    /// Opened after the viewer signs up; `None` - the home page.
+    redirect: Option<Route<'static>>,
 }
 
 impl Model {
@@ -37,9 +41,11 @@ impl From<Model> for Session {
 //     Init
 // ------ ------
 
-pub fn init(session: Session) -> Model {
+pub fn init(session: Session, redirect: Option<Route<'static>>) -> Model {
     Model {
         session,
+        redirect,
+        remember_me: storage::remember_me(),
         ..Model::default()
     }
 }
@@ -52,7 +58,13 @@ pub fn sink(g_msg: GMsg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>)
     match g_msg {
         GMsg::SessionChanged(session) => {
             model.session = session;
-            route::go_to(Route::Home, orders);
+            if model.session.viewer().is_some() {
+                let route = model
+                    .redirect
+                    .take()
+                    .unwrap_or(Route::Home(HomeQuery::default()));
+                route::go_to(route, orders);
+            }
         }
         _ => (),
     }
@@ -65,7 +77,8 @@ pub fn sink(g_msg: GMsg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>)
 pub enum Msg {
     FormSubmitted,
     FieldChanged(Field),
-    RegisterCompleted(Result<Viewer, Vec<Problem>>),
+    RememberMeToggled,
+    RegisterCompleted(Result<Viewer, ApiError>),
 }
 
 pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>) {
@@ -73,8 +86,8 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>)
         Msg::FormSubmitted => match model.form.trim_fields().validate() {
             Ok(valid_form) => {
                 model.problems.clear();
-                orders.perform_cmd(request::register::register(
-                    &valid_form,
+                orders.perform_cmd(api::cmd(
+                    model.session.api().register(&valid_form),
                     Msg::RegisterCompleted,
                 ));
             }
@@ -85,12 +98,15 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>)
         Msg::FieldChanged(field) => {
             model.form.upsert_field(field);
         }
+        Msg::RememberMeToggled => {
+            model.remember_me = !model.remember_me;
+        }
         Msg::RegisterCompleted(Ok(viewer)) => {
-            viewer.store();
-            orders.send_g_msg(GMsg::SessionChanged(Session::LoggedIn(viewer)));
+            storage::store_signed_in_viewer(&viewer, model.remember_me);
+            orders.send_g_msg(GMsg::SessionChanged(model.session.logged_in(viewer)));
         }
-        Msg::RegisterCompleted(Err(problems)) => {
-            model.problems = problems;
+        Msg::RegisterCompleted(Err(error)) => {
+            model.problems = error.into_problems();
         }
     }
 }
@@ -118,7 +134,10 @@ fn view_content(model: &Model) -> Node<Msg> {
                     p![
                         class!["text-xs-center"],
                         a![
-                            attrs! {At::Href => Route::Login.to_string()},
+                            attrs! {
+                                At::Href => Route::Login(model.redirect.clone().map(Box::new))
+                                    .to_string()
+                            },
                             "Have an account?"
                         ]
                     ],
@@ -126,7 +145,7 @@ fn view_content(model: &Model) -> Node<Msg> {
                         class!["error-messages"],
                         model.problems.iter().map(|problem| li![problem.message()])
                     ],
-                    view_form(&model.form)
+                    view_form(&model.form, model.remember_me)
                 ]
             ]
         ]
@@ -135,13 +154,14 @@ fn view_content(model: &Model) -> Node<Msg> {
 
 // ------ view form ------
 
-fn view_form(form: &Form) -> Node<Msg> {
+fn view_form(form: &Form, remember_me: bool) -> Node<Msg> {
     form![
         raw_ev(Ev::Submit, |event| {
             event.prevent_default();
             Msg::FormSubmitted
         }),
         form.iter_fields().map(view_fieldset),
+        view_remember_me(remember_me),
         button![
             class!["btn", "btn-lg", "btn-primary", "pull-xs-right"],
             "Sign up"
@@ -195,3 +215,19 @@ fn view_fieldset(field: &Field) -> Node<Msg> {
         ],
     }
 }
+
+fn view_remember_me(remember_me: bool) -> Node<Msg> {
+    fieldset![
+        class!["form-group"],
+        label![
+            input![
+                attrs! {
+                    At::Type => "checkbox",
+                    At::Checked => remember_me.as_at_value()
+                },
+                raw_ev(Ev::Change, |_| Msg::RememberMeToggled),
+            ],
+            " Remember me"
+        ]
+    ]
+}
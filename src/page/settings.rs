@@ -1,13 +1,18 @@
 use super::ViewPage;
 use seed::prelude::*;
+use std::num::{NonZeroU32, NonZeroUsize};
+use strum::IntoEnumIterator;
 
 use crate::{
+    api,
     entity::{
         form::settings::{Field, Form, Problem},
-        Viewer,
+        preferences::{DateFormat, HomeFeed, Theme, DEFAULT_COMMENT_DRAFT_MAX_AGE_DAYS},
+        ApiError, Preferences, Viewer,
     },
-    loading, request,
-    route::{self, Route},
+    loading, page, request,
+    route::Route,
+    storage::{self, backup::ImportMode},
     GMsg, Session,
 };
 
@@ -22,6 +27,10 @@ pub struct Model {
     session: Session,
     problems: Vec<Problem>,
     status: Status,
+    api_url: String,
+    /// Content of the chosen backup file waiting for the import mode.
+    backup: Option<String>,
+    backup_error: Option<String>,
 }
 
 impl Model {
@@ -56,14 +65,18 @@ impl Default for Status {
 // ------ ------
 
 pub fn init(session: Session, orders: &mut impl Orders<Msg, GMsg>) -> Model {
-    orders
-        .perform_cmd(loading::notify_on_slow_load(Msg::SlowLoadThresholdPassed))
-        .perform_cmd(request::settings::load(
-            session.viewer(),
-            Msg::FormLoadCompleted,
-        ));
+    // Guests have only the local settings - preferences, backup and backend.
+    if session.viewer().is_some() {
+        orders
+            .perform_cmd(loading::notify_on_slow_load(Msg::SlowLoadThresholdPassed))
+            .perform_cmd(api::cmd(
+                session.api().load_settings(session.viewer()),
+                Msg::FormLoadCompleted,
+            ));
+    }
     Model {
         session,
+        api_url: request::base_url(),
         ..Model::default()
     }
 }
@@ -75,8 +88,8 @@ pub fn init(session: Session, orders: &mut impl Orders<Msg, GMsg>) -> Model {
 pub fn sink(g_msg: GMsg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>) {
     match g_msg {
         GMsg::SessionChanged(session) => {
-            model.session = session;
-            route::go_to(Route::Home, orders);
+            // Reload the profile form for the new viewer (e.g. after it has been refreshed).
+            *model = init(session, orders);
         }
         _ => (),
     }
@@ -89,9 +102,16 @@ pub fn sink(g_msg: GMsg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>)
 pub enum Msg {
     FormSubmitted,
     FieldChanged(Field),
-    FormLoadCompleted(Result<Form, Vec<Problem>>),
-    SaveCompleted(Result<Viewer, Vec<Problem>>),
+    FormLoadCompleted(Result<Form, ApiError>),
+    SaveCompleted(Result<Viewer, ApiError>),
     SlowLoadThresholdPassed,
+    ApiUrlChanged(String),
+    ApiUrlSubmitted,
+    ApiUrlResetClicked,
+    PreferencesChanged(Preferences),
+    BackupFileChosen(Option<web_sys::File>),
+    BackupFileRead(Result<String, String>),
+    ImportClicked(ImportMode),
 }
 
 pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>) {
@@ -101,9 +121,11 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>)
                 match form.trim_fields().validate() {
                     Ok(valid_form) => {
                         model.problems.clear();
-                        orders.perform_cmd(request::settings::update(
-                            model.session.viewer(),
-                            &valid_form,
+                        orders.perform_cmd(api::cmd(
+                            model
+                                .session
+                                .api()
+                                .update_settings(model.session.viewer(), &valid_form),
                             Msg::SaveCompleted,
                         ));
                     }
@@ -121,25 +143,114 @@ pub fn update(msg: Msg, model: &mut Model, orders: &mut impl Orders<Msg, GMsg>)
         Msg::FormLoadCompleted(Ok(form)) => {
             model.status = Status::Loaded(form);
         }
-        Msg::FormLoadCompleted(Err(problems)) => {
-            model.problems = problems;
+        Msg::FormLoadCompleted(Err(error)) => {
+            model.problems = error.into_problems();
             model.status = Status::Failed;
         }
         Msg::SaveCompleted(Ok(viewer)) => {
             viewer.store();
-            orders.send_g_msg(GMsg::SessionChanged(Session::LoggedIn(viewer)));
+            orders.send_g_msg(GMsg::SessionChanged(model.session.logged_in(viewer)));
         }
-        Msg::SaveCompleted(Err(problems)) => {
-            model.problems = problems;
+        Msg::SaveCompleted(Err(error)) => {
+            model.problems = error.into_problems();
         }
         Msg::SlowLoadThresholdPassed => {
             if let Status::Loading = model.status {
                 model.status = Status::LoadingSlowly
             }
         }
+        Msg::ApiUrlChanged(api_url) => {
+            model.api_url = api_url;
+        }
+        Msg::ApiUrlSubmitted => {
+            let api_url = model.api_url.trim();
+            if api_url.is_empty() {
+                model.problems = vec![Problem::new_invalid_field(
+                    "api_url",
+                    "API URL can't be blank",
+                )];
+            } else {
+                storage::store_api_url(api_url);
+                switch_backend(api_url, orders);
+            }
+        }
+        Msg::ApiUrlResetClicked => {
+            storage::delete_api_url();
+            let old_base_url = request::base_url();
+            request::init_base_url();
+            model.api_url = request::base_url();
+            if model.api_url != old_base_url {
+                end_session(orders);
+            }
+        }
+        Msg::PreferencesChanged(preferences) => {
+            storage::store_preferences(model.session.viewer().map(Viewer::username), &preferences);
+            page::apply_theme(preferences.theme);
+            model.session = model.session.clone().with_preferences(preferences);
+        }
+        Msg::BackupFileChosen(Some(file)) => {
+            model.backup = None;
+            model.backup_error = None;
+            orders.perform_cmd(read_file(file));
+        }
+        Msg::BackupFileChosen(None) => {
+            model.backup = None;
+        }
+        Msg::BackupFileRead(Ok(backup)) => {
+            model.backup = Some(backup);
+        }
+        Msg::BackupFileRead(Err(error)) => {
+            model.backup_error = Some(error);
+        }
+        Msg::ImportClicked(mode) => {
+            if let Some(backup) = &model.backup {
+                match storage::backup::import(backup, mode) {
+                    Ok(()) => reload_session(orders),
+                    Err(error) => model.backup_error = Some(format!("Import failed: {}", error)),
+                }
+            }
+        }
+    }
+}
+
+async fn read_file(file: web_sys::File) -> Result<Msg, Msg> {
+    let text = wasm_bindgen_futures::JsFuture::from(file.text())
+        .await
+        .ok()
+        .and_then(|text| text.as_string())
+        .ok_or_else(|| "Can't read the file.".to_owned());
+    Ok(Msg::BackupFileRead(text))
+}
+
+/// Imported data may contain other viewers or even another backend.
+fn reload_session(orders: &mut impl Orders<Msg, GMsg>) {
+    request::init_base_url();
+    let session = Session::new(
+        storage::load_viewer(),
+        api::for_base_url(&request::base_url()),
+    )
+    .with_accounts(storage::load_viewers());
+    orders.send_g_msg(GMsg::SessionChanged(session));
+}
+
+fn switch_backend(api_url: &str, orders: &mut impl Orders<Msg, GMsg>) {
+    if api_url.trim_end_matches('/') != request::base_url() {
+        request::set_base_url(api_url);
+        end_session(orders);
     }
 }
 
+/// Tokens and cached user data belong to the old backend, so the session is cleared.
+///
+/// Drafts and preferences are kept - they aren't tied to the backend.
+fn end_session(orders: &mut impl Orders<Msg, GMsg>) {
+    storage::delete_sessions();
+    orders.send_g_msg(GMsg::SessionChanged(Session::new(
+        None,
+        api::for_base_url(&request::base_url()),
+    )));
+}
+
 // ------ ------
 //     View
 // ------ ------
@@ -169,8 +280,20 @@ fn view_content(model: &Model) -> Node<Msg> {
                             view_form(model),
                         ]
                     } else {
-                        vec![div!["Sign in to view your settings."]]
-                    }
+                        vec![p![
+                            a![
+                                attrs! {
+                                    At::Href => Route::Login(Some(Box::new(Route::Settings)))
+                                        .to_string()
+                                },
+                                "Sign in"
+                            ],
+                            " to edit your profile."
+                        ]]
+                    },
+                    view_preferences_form(model.session.preferences()),
+                    view_backup_form(model),
+                    view_backend_form(model),
                 ]
             ]
         ]
@@ -198,6 +321,223 @@ fn view_form(model: &Model) -> Node<Msg> {
     }
 }
 
+/// Preferences are saved on each change; they aren't sent to the server.
+fn view_preferences_form(preferences: &Preferences) -> Vec<Node<Msg>> {
+    let articles_per_page_options = vec![(None, "Default".to_owned())]
+        .into_iter()
+        .chain(
+            [5, 10, 20, 50]
+                .iter()
+                .filter_map(|count| NonZeroUsize::new(*count))
+                .map(|count| (Some(count), count.to_string())),
+        )
+        .collect();
+    let comment_draft_max_age_options = vec![(
+        None,
+        format!("Default ({} days)", DEFAULT_COMMENT_DRAFT_MAX_AGE_DAYS),
+    )]
+    .into_iter()
+    .chain(
+        [(1, "1 day"), (30, "30 days"), (365, "1 year")]
+            .iter()
+            .filter_map(|(days, label)| Some((Some(NonZeroU32::new(*days)?), (*label).to_owned()))),
+    )
+    .collect();
+
+    vec![
+        hr![],
+        h4!["Preferences"],
+        form![
+            view_preference(
+                "Default feed",
+                HomeFeed::iter()
+                    .map(|home_feed| (home_feed, home_feed.label().to_owned()))
+                    .collect(),
+                preferences.home_feed,
+                preferences.clone(),
+                |home_feed, preferences| Preferences {
+                    home_feed,
+                    ..preferences
+                },
+            ),
+            view_preference(
+                "Articles per page",
+                articles_per_page_options,
+                preferences.articles_per_page,
+                preferences.clone(),
+                |articles_per_page, preferences| Preferences {
+                    articles_per_page,
+                    ..preferences
+                },
+            ),
+            view_preference(
+                "Date format",
+                DateFormat::iter()
+                    .map(|date_format| (date_format, date_format.label().to_owned()))
+                    .collect(),
+                preferences.date_format,
+                preferences.clone(),
+                |date_format, preferences| Preferences {
+                    date_format,
+                    ..preferences
+                },
+            ),
+            view_preference(
+                "Theme",
+                Theme::iter()
+                    .map(|theme| (theme, theme.label().to_owned()))
+                    .collect(),
+                preferences.theme,
+                preferences.clone(),
+                |theme, preferences| Preferences {
+                    theme,
+                    ..preferences
+                },
+            ),
+            view_preference(
+                "Keep unposted comments",
+                comment_draft_max_age_options,
+                preferences.comment_draft_max_age_days,
+                preferences.clone(),
+                |comment_draft_max_age_days, preferences| Preferences {
+                    comment_draft_max_age_days,
+                    ..preferences
+                },
+            ),
+        ],
+    ]
+}
+
+fn view_backup_form(model: &Model) -> Vec<Node<Msg>> {
+    vec![
+        hr![],
+        h4!["Local data"],
+        p!["Viewers, drafts, preferences and other data saved in this browser."],
+        ul![
+            class!["error-messages"],
+            model.backup_error.iter().map(|error| li![error])
+        ],
+        fieldset![
+            class!["form-group"],
+            input![
+                class!["form-control"],
+                attrs! {At::Type => "file", At::Accept => ".json,application/json"},
+                raw_ev(Ev::Change, |event| {
+                    let file = event
+                        .target()
+                        .and_then(|target| target.dyn_into::<web_sys::HtmlInputElement>().ok())
+                        .and_then(|input| input.files())
+                        .and_then(|files| files.get(0));
+                    Msg::BackupFileChosen(file)
+                }),
+            ]
+        ],
+        a![
+            class!["btn", "btn-outline-secondary"],
+            attrs! {
+                At::Href => format!(
+                    "data:application/json;charset=utf-8,{}",
+                    js_sys::encode_uri_component(&storage::backup::export())
+                ),
+                At::Download => "conduit-backup.json",
+            },
+            "Export"
+        ],
+        if model.backup.is_some() {
+            div![
+                button![
+                    class!["btn", "btn-outline-danger", "pull-xs-right"],
+                    attrs! {At::Type => "button"},
+                    raw_ev(Ev::Click, |_| Msg::ImportClicked(ImportMode::Replace)),
+                    "Import and replace"
+                ],
+                button![
+                    class!["btn", "btn-outline-primary", "pull-xs-right"],
+                    style! {"margin-right" => "10px"},
+                    attrs! {At::Type => "button"},
+                    raw_ev(Ev::Click, |_| Msg::ImportClicked(ImportMode::Merge)),
+                    "Import and merge"
+                ],
+            ]
+        } else {
+            empty![]
+        },
+    ]
+}
+
+/// Select with `options` (values and their labels); `update` applies the selected value.
+fn view_preference<T: Copy + PartialEq + 'static>(
+    label: &str,
+    options: Vec<(T, String)>,
+    selected: T,
+    preferences: Preferences,
+    update: fn(T, Preferences) -> Preferences,
+) -> Node<Msg> {
+    let values = options.iter().map(|(value, _)| *value).collect::<Vec<_>>();
+    fieldset![
+        class!["form-group"],
+        label![label],
+        select![
+            class!["form-control"],
+            options
+                .into_iter()
+                .enumerate()
+                .map(|(index, (value, text))| option![
+                    attrs! {
+                        At::Value => index,
+                        At::Selected => (value == selected).as_at_value()
+                    },
+                    text
+                ])
+                .collect::<Vec<_>>(),
+            // Option values are indices into `values`.
+            input_ev(Ev::Change, move |index| {
+                let value = index
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| values.get(index).copied())
+                    .unwrap_or(selected);
+                Msg::PreferencesChanged(update(value, preferences))
+            }),
+        ]
+    ]
+}
+
+fn view_backend_form(model: &Model) -> Vec<Node<Msg>> {
+    vec![
+        hr![],
+        h4!["Backend"],
+        form![
+            raw_ev(Ev::Submit, |event| {
+                event.prevent_default();
+                Msg::ApiUrlSubmitted
+            }),
+            fieldset![
+                class!["form-group"],
+                input![
+                    class!["form-control"],
+                    attrs! {
+                        At::Type => "text",
+                        At::Placeholder => "API URL",
+                        At::Value => model.api_url
+                    },
+                    input_ev(Ev::Input, Msg::ApiUrlChanged),
+                ]
+            ],
+            button![
+                class!["btn", "btn-outline-secondary"],
+                attrs! {At::Type => "button"},
+                raw_ev(Ev::Click, |_| Msg::ApiUrlResetClicked),
+                "Reset to default"
+            ],
+            button![
+                class!["btn", "btn-outline-danger", "pull-xs-right"],
+                "Switch backend"
+            ]
+        ],
+    ]
+}
+
 fn view_fieldset(field: &Field) -> Node<Msg> {
     match field {
         Field::Avatar(value) => fieldset![
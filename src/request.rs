@@ -1,73 +1,428 @@
 use crate::{
     coder::decoder,
-    entity::{form::Problem, ErrorMessage, Viewer},
-    logger,
+    entity::{auth_token, ApiError, Viewer},
+    logger, storage,
 };
-use seed::fetch;
+use gloo_timers::future::TimeoutFuture;
+use indexmap::IndexMap;
+use instrumentation::RequestRecord;
+use retry::RetryPolicy;
+use seed::{fetch, prelude::*};
+use serde::{de::DeserializeOwned, Serialize};
 use serde_json;
-use std::fmt::Debug;
+use std::{cell::RefCell, convert::identity, fmt::Debug, rc::Rc};
+use wasm_bindgen_futures::spawn_local;
 
 pub mod article;
 pub mod author;
+pub mod cache;
 pub mod comment;
 pub mod favorite;
 pub mod feed;
 pub mod follow;
+pub mod instrumentation;
 pub mod login;
 pub mod register;
+pub mod retry;
 pub mod settings;
 pub mod tag;
+pub mod viewer;
 
-static BASE_API_URL: &str = "https://conduit.productionready.io/api";
+pub static DEFAULT_BASE_API_URL: &str = "https://conduit.productionready.io/api";
+static BASE_API_URL_META_NAME: &str = "conduit-api-url";
 const TIMEOUT: u32 = 5000;
+/// Default for GET requests - see `Request::retry_policy`. Mutations are never sent twice.
+const DEFAULT_RETRY_POLICY: RetryPolicy = RetryPolicy {
+    max_attempts: 3,
+    base_delay_ms: 300,
+    max_delay_ms: 5000,
+};
+
+thread_local! {
+    static BASE_API_URL: RefCell<String> = RefCell::new(DEFAULT_BASE_API_URL.into());
+    static CACHE: RefCell<cache::Cache> = RefCell::new(cache::Cache::default());
+}
+
+/// Resolve the API base URL at startup.
+///
+/// Priority: storage override > `<meta name="conduit-api-url">` in `index.html` > default.
+pub fn init_base_url() {
+    let base_url = storage::load_api_url()
+        .or_else(base_url_from_meta)
+        .unwrap_or_else(|| DEFAULT_BASE_API_URL.into());
+    set_base_url(base_url);
+}
+
+pub fn base_url() -> String {
+    BASE_API_URL.with(|base_url| base_url.borrow().clone())
+}
+
+pub fn set_base_url(base_url: impl Into<String>) {
+    let base_url = base_url.into();
+    BASE_API_URL.with(|old_base_url| {
+        base_url
+            .trim_end_matches('/')
+            .clone_into(&mut old_base_url.borrow_mut());
+    });
+}
+
+pub fn new(path: &str, viewer: Option<&Viewer>) -> Request {
+    Request {
+        url: format!("{}/{}", base_url(), path),
+        path: path.to_owned(),
+        method: fetch::Method::Get,
+        auth_token: viewer.map(|viewer| viewer.auth_token.clone()),
+        json_body: None,
+        cache_ttl_ms: None,
+        invalidated_urls: Vec::new(),
+        retry_policy: Some(DEFAULT_RETRY_POLICY),
+    }
+}
+
+// ------ Request ------
+
+/// Description of a request to the Conduit API.
+///
+/// A new `fetch::Request` is built for each attempt
+/// because its abort controller can't be reused once it has timed out.
+pub struct Request {
+    url: String,
+    /// Path relative to the base URL - for instrumentation.
+    path: String,
+    method: fetch::Method,
+    auth_token: Option<String>,
+    json_body: Option<String>,
+    cache_ttl_ms: Option<u32>,
+    invalidated_urls: Vec<String>,
+    /// `None` for mutating methods.
+    retry_policy: Option<RetryPolicy>,
+}
+
+impl Request {
+    /// Mutating methods aren't retried.
+    pub const fn method(mut self, method: fetch::Method) -> Self {
+        if !matches!(method, fetch::Method::Get) {
+            self.retry_policy = None;
+        }
+        self.method = method;
+        self
+    }
+
+    /// Replace `DEFAULT_RETRY_POLICY` of this GET request.
+    pub const fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        if matches!(self.method, fetch::Method::Get) {
+            self.retry_policy = Some(retry_policy);
+        }
+        self
+    }
+
+    pub fn send_json<T: Serialize>(mut self, data: &T) -> Self {
+        self.json_body = Some(serde_json::to_string(data).expect("serialize body to JSON failed"));
+        self
+    }
+
+    /// Cache the successful response of this GET request.
+    ///
+    /// Cached data older than `ttl_ms` are still served, but they're refreshed in the background.
+    /// The refreshed data only warm the cache for the next request - the caller isn't notified.
+    pub const fn cache(mut self, ttl_ms: u32) -> Self {
+        self.cache_ttl_ms = Some(ttl_ms);
+        self
+    }
+
+    /// Remove cached responses of all requests with the path starting with `path`
+    /// once this request succeeds.
+    pub fn invalidate(mut self, path: &str) -> Self {
+        self.invalidated_urls
+            .push(format!("{}/{}", base_url(), path));
+        self
+    }
+
+    /// Same as `fetch::Request::fetch_json_data`, but GET requests are retried and may be cached.
+    pub async fn fetch_json_data<T, U>(
+        self,
+        f: impl FnOnce(fetch::ResponseDataResult<T>) -> U,
+    ) -> Result<U, U>
+    where
+        T: DeserializeOwned + 'static,
+        U: 'static,
+    {
+        match self.cached_json() {
+            cache::Lookup::Fresh(json) => {
+                if let Ok(data) = serde_json::from_str(&json) {
+                    self.record_cache_hit(&json);
+                    return Ok(f(Ok(data)));
+                }
+            }
+            cache::Lookup::Stale(json) => {
+                if let Ok(data) = serde_json::from_str(&json) {
+                    self.record_cache_hit(&json);
+                    // The caller gets the stale data; the fresh data are served next time.
+                    spawn_local(self.revalidate());
+                    return Ok(f(Ok(data)));
+                }
+            }
+            cache::Lookup::Miss => (),
+        }
+
+        let fetch_object = self.fetch_string().await;
+        self.update_cache(&fetch_object);
+        Ok(f(decode_json(fetch_object).response_data()))
+    }
+
+    // ====== PRIVATE ======
+
+    async fn fetch_string(&self) -> fetch::FetchObject<String> {
+        if self
+            .auth_token
+            .as_deref()
+            .is_some_and(|auth_token| auth_token::is_expired(auth_token, js_sys::Date::now()))
+        {
+            return self.unauthorized_fetch_object();
+        }
+
+        let mut attempt = 1;
+        loop {
+            let started_at = js_sys::Date::now();
+            let fetch_object = self
+                .to_fetch_request()
+                .fetch_string(identity)
+                .await
+                .unwrap_or_else(identity);
+            self.record_attempt(&fetch_object, attempt, js_sys::Date::now() - started_at);
 
-pub fn new(path: &str, viewer: Option<&Viewer>) -> fetch::Request {
-    let mut request = fetch::Request::new(format!("{}/{}", BASE_API_URL, path)).timeout(TIMEOUT);
+            match self.retry_delay(&fetch_object, attempt) {
+                Some(delay) => {
+                    TimeoutFuture::new(delay).await;
+                    attempt += 1;
+                }
+                None => return fetch_object,
+            }
+        }
+    }
+
+    async fn revalidate(self) {
+        let fetch_object = self.fetch_string().await;
+        self.update_cache(&fetch_object);
+    }
+
+    fn record_attempt(
+        &self,
+        fetch_object: &fetch::FetchObject<String>,
+        attempt: u32,
+        duration_ms: f64,
+    ) {
+        let (status, payload_size) = match &fetch_object.result {
+            Ok(response) => (
+                Some(response.status.code),
+                response.data.as_ref().map_or(0, String::len),
+            ),
+            Err(_) => (None, 0),
+        };
+        instrumentation::record(&RequestRecord {
+            method: self.method_name(),
+            path: self.path.clone(),
+            status,
+            attempt,
+            duration_ms,
+            payload_size,
+            from_cache: false,
+        });
+    }
+
+    fn record_cache_hit(&self, json: &str) {
+        instrumentation::record(&RequestRecord {
+            method: self.method_name(),
+            path: self.path.clone(),
+            status: None,
+            attempt: 0,
+            duration_ms: 0.,
+            payload_size: json.len(),
+            from_cache: true,
+        });
+    }
 
-    if let Some(viewer) = viewer {
-        let auth_token = viewer.auth_token.as_str();
-        request = request.header("authorization", &format!("Token {}", auth_token));
+    fn method_name(&self) -> String {
+        format!("{:?}", self.method).to_uppercase()
+    }
+
+    fn cached_json(&self) -> cache::Lookup {
+        match (self.method, self.cache_ttl_ms) {
+            (fetch::Method::Get, Some(_)) => CACHE.with(|cache| {
+                cache
+                    .borrow()
+                    .get(&self.url, self.auth_token.as_deref(), js_sys::Date::now())
+            }),
+            _ => cache::Lookup::Miss,
+        }
+    }
+
+    fn update_cache(&self, fetch_object: &fetch::FetchObject<String>) {
+        let json = match &fetch_object.result {
+            Ok(response) if response.status.is_ok() => match &response.data {
+                Ok(json) => json,
+                Err(_) => return,
+            },
+            _ => return,
+        };
+        CACHE.with(|cache| {
+            let mut cache = cache.borrow_mut();
+            if let (fetch::Method::Get, Some(ttl_ms)) = (self.method, self.cache_ttl_ms) {
+                cache.insert(
+                    self.url.clone(),
+                    self.auth_token.clone(),
+                    json.clone(),
+                    ttl_ms,
+                    js_sys::Date::now(),
+                );
+            }
+            for url in &self.invalidated_urls {
+                cache.invalidate(url);
+            }
+        });
+    }
+
+    /// Response "401 Unauthorized" made without sending the request.
+    fn unauthorized_fetch_object(&self) -> fetch::FetchObject<String> {
+        let response_init = web_sys::ResponseInit::new();
+        response_init.set_status(401);
+        response_init.set_status_text("Unauthorized");
+        let raw = web_sys::Response::new_with_opt_str_and_init(None, &response_init)
+            .expect("create response failed");
+        fetch::FetchObject {
+            request: self.to_fetch_request(),
+            result: Ok(fetch::ResponseWithDataResult {
+                status: fetch::Status::from(&raw),
+                raw,
+                data: Ok(String::new()),
+            }),
+        }
+    }
+
+    fn to_fetch_request(&self) -> fetch::Request {
+        let mut request = fetch::Request::new(self.url.clone())
+            .method(self.method)
+            .timeout(TIMEOUT);
+
+        if let Some(auth_token) = &self.auth_token {
+            request = request.header("authorization", &format!("Token {}", auth_token));
+        }
+        if let Some(json_body) = &self.json_body {
+            request = request
+                .header("Content-Type", "application/json; charset=utf-8")
+                .body(JsValue::from_str(json_body));
+        }
+        request
+    }
+
+    fn retry_delay<T>(&self, fetch_object: &fetch::FetchObject<T>, attempt: u32) -> Option<u32> {
+        let retry_policy = self.retry_policy?;
+        if !retry_policy.can_retry(attempt) {
+            return None;
+        }
+        let jitter = js_sys::Math::random();
+
+        match &fetch_object.result {
+            // Network error or timeout.
+            Err(_) => retry_policy.delay_ms(attempt, None, jitter),
+            Ok(response) if retry::is_retryable_status(response.status.code) => {
+                let retry_after = if retry::honours_retry_after(response.status.code) {
+                    response
+                        .raw
+                        .headers()
+                        .get("Retry-After")
+                        .ok()
+                        .flatten()
+                        .and_then(|retry_after| retry::parse_retry_after(&retry_after))
+                } else {
+                    None
+                };
+                retry_policy.delay_ms(attempt, retry_after, jitter)
+            }
+            Ok(_) => None,
+        }
     }
-    request
 }
 
-pub fn fail_reason_into_problems<T: Debug>(fail_reason: fetch::FailReason<T>) -> Vec<Problem> {
-    fail_reason_into_errors(fail_reason)
-        .into_iter()
-        .map(|error| Problem::new_server_error(error.into_inner()))
-        .collect()
+/// Same as the deserialization in `fetch::Request::fetch_json`.
+fn decode_json<T: DeserializeOwned>(
+    fetch_object: fetch::FetchObject<String>,
+) -> fetch::FetchObject<T> {
+    let fetch::FetchObject { request, result } = fetch_object;
+    let result = result.map(|response| fetch::ResponseWithDataResult {
+        raw: response.raw,
+        status: response.status,
+        data: response
+            .data
+            .and_then(|json| match serde_json::from_str(&json) {
+                Ok(data) => Ok(data),
+                Err(error) => Err(fetch::DataError::SerdeError(Rc::new(error), json)),
+            }),
+    });
+    fetch::FetchObject { request, result }
 }
 
-pub fn fail_reason_into_errors<T: Debug>(fail_reason: fetch::FailReason<T>) -> Vec<ErrorMessage> {
+// ------ errors ------
+
+pub fn fail_reason_into_api_error<T: Debug>(fail_reason: fetch::FailReason<T>) -> ApiError {
     match fail_reason {
         fetch::FailReason::RequestError(request_error, _) => {
-            logger::error(request_error);
-            vec!["Request error".into()]
+            logger::error(&request_error);
+            match request_error {
+                // `fetch::Request` aborts the request when the timeout expires.
+                fetch::RequestError::DomException(exception)
+                    if exception.name() == "AbortError" =>
+                {
+                    ApiError::Timeout
+                }
+                fetch::RequestError::DomException(_) => ApiError::Network,
+            }
         }
         fetch::FailReason::DataError(data_error, _) => {
-            logger::error(data_error);
-            vec!["Data error".into()]
-        }
-        fetch::FailReason::Status(_, fetch_object) => {
-            // response isn't ok, but maybe contains error messages - try to decode them:
-            match fetch_object.result.unwrap().data {
-                Err(fetch::DataError::SerdeError(_, json)) => decode_server_errors(&json)
-                    .unwrap_or_else(|serde_error| {
-                        logger::error(serde_error);
-                        vec!["Data error".into()]
-                    }),
-                data => {
-                    logger::error(data);
-                    vec!["Data error".into()]
+            logger::error(&data_error);
+            match data_error {
+                fetch::DataError::SerdeError(serde_error, _) => {
+                    ApiError::Decode(serde_error.to_string().into())
                 }
+                fetch::DataError::DomException(_) => ApiError::Decode("Data error".into()),
             }
         }
+        fetch::FailReason::Status(status, fetch_object) => match status.code {
+            401 => ApiError::Unauthorized,
+            403 => ApiError::Forbidden,
+            404 => ApiError::NotFound,
+            code if status.category == fetch::StatusCategory::ClientError => {
+                // response isn't ok, but maybe contains error messages - try to decode them:
+                match fetch_object.result.map(|response| response.data) {
+                    Ok(Err(fetch::DataError::SerdeError(_, json))) => decode_server_errors(&json)
+                        .map(ApiError::Validation)
+                        .unwrap_or_else(|serde_error| {
+                            logger::error(serde_error);
+                            ApiError::Server(code)
+                        }),
+                    data => {
+                        logger::error(data);
+                        ApiError::Server(code)
+                    }
+                }
+            }
+            code => ApiError::Server(code),
+        },
     }
 }
 
 // ====== PRIVATE ======
 
-fn decode_server_errors(json: &str) -> Result<Vec<ErrorMessage>, serde_json::Error> {
+fn base_url_from_meta() -> Option<String> {
+    seed::document()
+        .query_selector(&format!("meta[name='{}']", BASE_API_URL_META_NAME))
+        .ok()
+        .flatten()
+        .and_then(|meta| meta.get_attribute("content"))
+        .filter(|content| !content.trim().is_empty())
+}
+
+fn decode_server_errors(json: &str) -> Result<IndexMap<String, Vec<String>>, serde_json::Error> {
     serde_json::from_str::<decoder::ErrorMessages>(json)
-        .map(decoder::ErrorMessages::into_error_messages)
+        .map(decoder::ErrorMessages::into_field_errors)
 }
@@ -4,7 +4,7 @@ use indexmap::IndexMap;
 use seed::fetch::{Method, ResponseDataResult};
 
 use crate::{
-    entity::{ErrorMessage, Slug, Viewer},
+    entity::{ApiError, Slug, Viewer},
     request,
 };
 
@@ -13,13 +13,15 @@ type RootDecoder = IndexMap<(), ()>;
 pub fn delete<Ms: 'static>(
     viewer: Option<&Viewer>,
     slug: &Slug,
-    f: fn(Result<(), Vec<ErrorMessage>>) -> Ms,
+    f: fn(Result<(), ApiError>) -> Ms,
 ) -> impl Future<Output = Result<Ms, Ms>> {
     request::new(&format!("articles/{}", slug.as_str()), viewer)
         .method(Method::Delete)
+        .invalidate("articles")
+        .invalidate("tags")
         .fetch_json_data(move |data_result: ResponseDataResult<RootDecoder>| {
             f(data_result
                 .map(move |_| ())
-                .map_err(request::fail_reason_into_errors))
+                .map_err(request::fail_reason_into_api_error))
         })
 }
@@ -5,7 +5,7 @@ use serde::Deserialize;
 
 use crate::{
     coder::decoder,
-    entity::{Article, ErrorMessage, Slug, Viewer},
+    entity::{ApiError, Article, Slug, Viewer},
     request,
 };
 
@@ -18,18 +18,18 @@ struct RootDecoder {
 pub fn load<Ms: 'static>(
     viewer: Option<Viewer>,
     slug: &Slug,
-    f: fn(Result<Article, Vec<ErrorMessage>>) -> Ms,
+    f: fn(Result<Article, ApiError>) -> Ms,
 ) -> impl Future<Output = Result<Ms, Ms>> {
-    request::new(&format!("articles/{}", slug.as_str()), viewer.as_ref()).fetch_json_data(
-        move |data_result: ResponseDataResult<RootDecoder>| {
+    request::new(&format!("articles/{}", slug.as_str()), viewer.as_ref())
+        .cache(request::cache::ARTICLES_TTL_MS)
+        .fetch_json_data(move |data_result: ResponseDataResult<RootDecoder>| {
             f(data_result
-                .map_err(request::fail_reason_into_errors)
+                .map_err(request::fail_reason_into_api_error)
                 .and_then(move |root_decoder| {
                     root_decoder
                         .article
                         .try_into_article(viewer.map(Cow::Owned))
-                        .map_err(|error| vec![error])
+                        .map_err(ApiError::Decode)
                 }))
-        },
-    )
+        })
 }
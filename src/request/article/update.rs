@@ -5,10 +5,7 @@ use serde::Deserialize;
 
 use crate::{
     coder::decoder,
-    entity::{
-        form::article_editor::{Problem, ValidForm},
-        Article, Slug, Viewer,
-    },
+    entity::{form::article_editor::ValidForm, ApiError, Article, Slug, Viewer},
     request,
 };
 
@@ -22,19 +19,21 @@ pub fn update<Ms: 'static>(
     viewer: Option<Viewer>,
     valid_form: &ValidForm,
     slug: &Slug,
-    f: fn(Result<Article, Vec<Problem>>) -> Ms,
+    f: fn(Result<Article, ApiError>) -> Ms,
 ) -> impl Future<Output = Result<Ms, Ms>> {
     request::new(&format!("articles/{}", slug.as_str()), viewer.as_ref())
         .method(Method::Put)
+        .invalidate("articles")
+        .invalidate("tags")
         .send_json(&valid_form.to_encoder())
         .fetch_json_data(move |data_result: ResponseDataResult<RootDecoder>| {
             f(data_result
-                .map_err(request::fail_reason_into_problems)
+                .map_err(request::fail_reason_into_api_error)
                 .and_then(move |root_decoder| {
                     root_decoder
                         .article
                         .try_into_article(viewer.map(Cow::Owned))
-                        .map_err(|error| vec![Problem::new_server_error(error.into_inner())])
+                        .map_err(ApiError::Decode)
                 }))
         })
 }
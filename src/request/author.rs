@@ -1,6 +1,6 @@
 use crate::{
     coder::decoder,
-    entity::{Author, ErrorMessage, Username, Viewer},
+    entity::{ApiError, Author, Username, Viewer},
     request,
 };
 use seed::fetch::ResponseDataResult;
@@ -17,14 +17,15 @@ struct RootDecoder {
 pub async fn load<Ms: 'static>(
     viewer: Option<Viewer>,
     username: Username<'static>,
-    f: fn(Result<Author, (Username<'static>, Vec<ErrorMessage>)>) -> Ms,
+    f: fn(Result<Author, (Username<'static>, ApiError)>) -> Ms,
 ) -> Result<Ms, Ms> {
     request::new(&format!("profiles/{}", username.as_str()), viewer.as_ref())
+        .cache(request::cache::PROFILES_TTL_MS)
         .fetch_json_data(move |data_result: ResponseDataResult<RootDecoder>| {
             f(data_result
                 .map(move |root_decoder| root_decoder.profile.into_author(viewer.map(Cow::Owned)))
-                .map_err(request::fail_reason_into_errors)
-                .map_err(move |errors| (username, errors)))
+                .map_err(request::fail_reason_into_api_error)
+                .map_err(move |error| (username, error)))
         })
         .await
 }
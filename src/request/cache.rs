@@ -0,0 +1,209 @@
+use std::collections::HashMap;
+
+pub const TAGS_TTL_MS: u32 = 5 * 60 * 1000;
+pub const ARTICLES_TTL_MS: u32 = 30 * 1000;
+pub const PROFILES_TTL_MS: u32 = 60 * 1000;
+pub const COMMENTS_TTL_MS: u32 = 15 * 1000;
+
+/// Expired entries are still served (and refreshed in the background) until they are this old.
+const MAX_STALE_AGE_MS: f64 = 10. * 60. * 1000.;
+/// The oldest entries are evicted when the cache is full.
+const MAX_ENTRIES: usize = 200;
+
+// ------ Lookup ------
+
+#[derive(Debug, PartialEq)]
+pub enum Lookup {
+    Fresh(String),
+    /// Serve it, but fetch a new version.
+    Stale(String),
+    Miss,
+}
+
+// ------ Cache ------
+
+/// Response bodies (JSON) of successful GET requests.
+/// Keyed by request URL and viewer's auth token, so viewers never see each other's data.
+#[derive(Default)]
+pub struct Cache {
+    entries: HashMap<(String, Option<String>), Entry>,
+}
+
+struct Entry {
+    json: String,
+    ttl_ms: u32,
+    stored_at: f64,
+}
+
+impl Entry {
+    fn is_too_old(&self, now: f64) -> bool {
+        now - self.stored_at >= f64::from(self.ttl_ms) + MAX_STALE_AGE_MS
+    }
+}
+
+impl Cache {
+    pub fn get(&self, url: &str, auth_token: Option<&str>, now: f64) -> Lookup {
+        let key = (url.to_owned(), auth_token.map(ToOwned::to_owned));
+        match self.entries.get(&key) {
+            Some(entry) => {
+                let age = now - entry.stored_at;
+                if age < f64::from(entry.ttl_ms) {
+                    Lookup::Fresh(entry.json.clone())
+                } else if !entry.is_too_old(now) {
+                    Lookup::Stale(entry.json.clone())
+                } else {
+                    Lookup::Miss
+                }
+            }
+            None => Lookup::Miss,
+        }
+    }
+
+    pub fn insert(
+        &mut self,
+        url: String,
+        auth_token: Option<String>,
+        json: String,
+        ttl_ms: u32,
+        now: f64,
+    ) {
+        let key = (url, auth_token);
+        if !self.entries.contains_key(&key) {
+            self.purge(now);
+        }
+        let entry = Entry {
+            json,
+            ttl_ms,
+            stored_at: now,
+        };
+        self.entries.insert(key, entry);
+    }
+
+    /// Remove entries of all viewers whose URL starts with `url_prefix`.
+    pub fn invalidate(&mut self, url_prefix: &str) {
+        self.entries
+            .retain(|(url, _), _| !url.starts_with(url_prefix));
+    }
+
+    // ====== PRIVATE ======
+
+    /// Remove entries too old to be served and make room for a new entry.
+    fn purge(&mut self, now: f64) {
+        self.entries.retain(|_, entry| !entry.is_too_old(now));
+        if self.entries.len() >= MAX_ENTRIES {
+            let oldest_key = self
+                .entries
+                .iter()
+                .min_by(|(_, a), (_, b)| a.stored_at.total_cmp(&b.stored_at))
+                .map(|(key, _)| key.clone());
+            if let Some(oldest_key) = oldest_key {
+                self.entries.remove(&oldest_key);
+            }
+        }
+    }
+}
+
+// ====== ====== TESTS ====== ======
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    const URL: &str = "https://example.com/api/tags";
+
+    fn cache_with_entry() -> Cache {
+        let mut cache = Cache::default();
+        cache.insert(URL.into(), None, "{}".into(), 1000, 0.);
+        cache
+    }
+
+    #[wasm_bindgen_test]
+    fn lookup_by_age_test() {
+        // ====== ARRANGE ======
+        let cache = cache_with_entry();
+
+        // ====== ACT & ASSERT ======
+        assert_eq!(cache.get(URL, None, 999.), Lookup::Fresh("{}".into()));
+        assert_eq!(cache.get(URL, None, 1000.), Lookup::Stale("{}".into()));
+        assert_eq!(cache.get(URL, None, 1000. + MAX_STALE_AGE_MS), Lookup::Miss);
+    }
+
+    #[wasm_bindgen_test]
+    fn lookup_per_viewer_test() {
+        // ====== ARRANGE ======
+        let cache = cache_with_entry();
+
+        // ====== ACT & ASSERT ======
+        assert_eq!(cache.get(URL, Some("token"), 0.), Lookup::Miss);
+    }
+
+    #[wasm_bindgen_test]
+    fn insert_purges_too_old_entries_test() {
+        // ====== ARRANGE ======
+        let mut cache = cache_with_entry();
+
+        // ====== ACT ======
+        cache.insert(
+            "https://example.com/api/profiles/john".into(),
+            None,
+            "{}".into(),
+            1000,
+            1000. + MAX_STALE_AGE_MS,
+        );
+
+        // ====== ASSERT ======
+        assert_eq!(cache.entries.len(), 1);
+    }
+
+    #[wasm_bindgen_test]
+    fn insert_evicts_oldest_entry_when_full_test() {
+        // ====== ARRANGE ======
+        let mut cache = cache_with_entry();
+        for index in 1..MAX_ENTRIES {
+            #[allow(clippy::cast_precision_loss)]
+            let now = index as f64;
+            cache.insert(format!("{}/{}", URL, index), None, "{}".into(), 1000, now);
+        }
+
+        // ====== ACT ======
+        cache.insert(
+            "https://example.com/api/articles".into(),
+            None,
+            "{}".into(),
+            1000,
+            500.,
+        );
+
+        // ====== ASSERT ======
+        assert_eq!(cache.entries.len(), MAX_ENTRIES);
+        assert_eq!(cache.get(URL, None, 500.), Lookup::Miss);
+    }
+
+    #[wasm_bindgen_test]
+    fn invalidate_test() {
+        // ====== ARRANGE ======
+        let mut cache = cache_with_entry();
+        cache.insert(URL.into(), Some("token".into()), "{}".into(), 1000, 0.);
+        cache.insert(
+            "https://example.com/api/profiles/john".into(),
+            None,
+            "{}".into(),
+            1000,
+            0.,
+        );
+
+        // ====== ACT ======
+        cache.invalidate("https://example.com/api/tags");
+
+        // ====== ASSERT ======
+        assert_eq!(cache.get(URL, None, 0.), Lookup::Miss);
+        assert_eq!(cache.get(URL, Some("token"), 0.), Lookup::Miss);
+        assert_eq!(
+            cache.get("https://example.com/api/profiles/john", None, 0.),
+            Lookup::Fresh("{}".into())
+        );
+    }
+}
@@ -5,7 +5,7 @@ use serde::Deserialize;
 
 use crate::{
     coder::{decoder, encoder},
-    entity::{Comment, ErrorMessage, Slug, Viewer},
+    entity::{ApiError, Comment, Slug, Viewer},
     request,
 };
 
@@ -19,22 +19,23 @@ pub fn create<Ms: 'static>(
     viewer: Option<Viewer>,
     slug: &Slug,
     text: String,
-    f: fn(Result<Comment, Vec<ErrorMessage>>) -> Ms,
+    f: fn(Result<Comment, ApiError>) -> Ms,
 ) -> impl Future<Output = Result<Ms, Ms>> {
     request::new(
         &format!("articles/{}/comments", slug.as_str()),
         viewer.as_ref(),
     )
     .method(Method::Post)
+    .invalidate(&format!("articles/{}/comments", slug.as_str()))
     .send_json(&encoder::Comment::new(text))
     .fetch_json_data(move |data_result: ResponseDataResult<RootDecoder>| {
         f(data_result
-            .map_err(request::fail_reason_into_errors)
+            .map_err(request::fail_reason_into_api_error)
             .and_then(move |root_decoder| {
                 root_decoder
                     .comment
                     .try_into_comment(viewer.map(Cow::Owned))
-                    .map_err(|error| vec![error])
+                    .map_err(ApiError::Decode)
             }))
     })
 }
@@ -5,7 +5,7 @@ use serde::Deserialize;
 
 use crate::{
     coder::decoder,
-    entity::{Comment, ErrorMessage, Slug, Viewer},
+    entity::{ApiError, Comment, Slug, Viewer},
     logger, request,
 };
 
@@ -35,15 +35,16 @@ impl RootDecoder {
 pub fn load_list<Ms: 'static>(
     viewer: Option<Viewer>,
     slug: &Slug,
-    f: fn(Result<VecDeque<Comment>, Vec<ErrorMessage>>) -> Ms,
+    f: fn(Result<VecDeque<Comment>, ApiError>) -> Ms,
 ) -> impl Future<Output = Result<Ms, Ms>> {
     request::new(
         &format!("articles/{}/comments", slug.as_str()),
         viewer.as_ref(),
     )
+    .cache(request::cache::COMMENTS_TTL_MS)
     .fetch_json_data(move |data_result: ResponseDataResult<RootDecoder>| {
         f(data_result
             .map(move |root_decoder| root_decoder.into_comments(viewer.as_ref()))
-            .map_err(request::fail_reason_into_errors))
+            .map_err(request::fail_reason_into_api_error))
     })
 }
@@ -1,6 +1,6 @@
 use crate::{
     coder::decoder,
-    entity::{Article, ErrorMessage, Slug, Viewer},
+    entity::{ApiError, Article, Slug, Viewer},
     request,
 };
 use seed::fetch::{Method, ResponseDataResult};
@@ -17,21 +17,22 @@ struct RootDecoder {
 pub fn favorite<Ms: 'static>(
     viewer: Option<Viewer>,
     slug: &Slug,
-    f: fn(Result<Article, Vec<ErrorMessage>>) -> Ms,
+    f: fn(Result<Article, ApiError>) -> Ms,
 ) -> impl Future<Output = Result<Ms, Ms>> {
     request::new(
         &format!("articles/{}/favorite", slug.as_str()),
         viewer.as_ref(),
     )
     .method(Method::Post)
+    .invalidate("articles")
     .fetch_json_data(move |data_result: ResponseDataResult<RootDecoder>| {
         f(data_result
-            .map_err(request::fail_reason_into_errors)
+            .map_err(request::fail_reason_into_api_error)
             .and_then(move |root_decoder| {
                 root_decoder
                     .article
                     .try_into_article(viewer.map(Cow::Owned))
-                    .map_err(|error| vec![error])
+                    .map_err(ApiError::Decode)
             }))
     })
 }
@@ -1,6 +1,6 @@
 use crate::{
     coder::decoder,
-    entity::{Article, ErrorMessage, PageNumber, PaginatedList, Viewer},
+    entity::{ApiError, Article, PageNumber, PaginatedList, Viewer},
     logger,
     page::home::SelectedFeed,
     request,
@@ -11,7 +11,7 @@ use serde::Deserialize;
 use std::{borrow::Cow, future::Future, num::NonZeroUsize};
 
 lazy_static! {
-    static ref ARTICLES_PER_PAGE: NonZeroUsize = NonZeroUsize::new(10).unwrap();
+    pub static ref ARTICLES_PER_PAGE: NonZeroUsize = NonZeroUsize::new(10).unwrap();
 }
 
 #[derive(Deserialize, Debug)]
@@ -22,7 +22,11 @@ struct RootDecoder {
 }
 
 impl RootDecoder {
-    fn into_paginated_list(self, viewer: &Option<Viewer>) -> PaginatedList<Article> {
+    fn into_paginated_list(
+        self,
+        viewer: &Option<Viewer>,
+        per_page: NonZeroUsize,
+    ) -> PaginatedList<Article> {
         PaginatedList {
             items: self
                 .articles
@@ -37,13 +41,17 @@ impl RootDecoder {
                     }
                 })
                 .collect(),
-            per_page: *ARTICLES_PER_PAGE,
+            per_page,
             total: self.articles_count,
         }
     }
 }
 
-pub fn request_url(selected_feed: &SelectedFeed, page_number: PageNumber) -> String {
+pub fn request_url(
+    selected_feed: &SelectedFeed,
+    page_number: PageNumber,
+    per_page: NonZeroUsize,
+) -> String {
     let (path, tag_param) = match selected_feed {
         SelectedFeed::Your(_) => (Some("/feed"), None),
         SelectedFeed::Global => (None, None),
@@ -51,8 +59,8 @@ pub fn request_url(selected_feed: &SelectedFeed, page_number: PageNumber) -> Str
     };
 
     let mut parameters = vec![
-        format!("limit={}", *ARTICLES_PER_PAGE),
-        format!("offset={}", (*page_number - 1) * ARTICLES_PER_PAGE.get()),
+        format!("limit={}", per_page),
+        format!("offset={}", (*page_number - 1) * per_page.get()),
     ];
     if let Some(tag_param) = tag_param {
         parameters.push(tag_param)
@@ -68,13 +76,17 @@ pub fn load_for_home<Ms: 'static>(
     viewer: Option<Viewer>,
     selected_feed: &SelectedFeed,
     page_number: PageNumber,
-    f: fn(Result<PaginatedList<Article>, Vec<ErrorMessage>>) -> Ms,
+    per_page: NonZeroUsize,
+    f: impl FnOnce(Result<PaginatedList<Article>, ApiError>) -> Ms + 'static,
 ) -> impl Future<Output = Result<Ms, Ms>> {
-    request::new(&request_url(selected_feed, page_number), viewer.as_ref()).fetch_json_data(
-        move |data_result: ResponseDataResult<RootDecoder>| {
-            f(data_result
-                .map(move |root_decoder| root_decoder.into_paginated_list(&viewer))
-                .map_err(request::fail_reason_into_errors))
-        },
+    request::new(
+        &request_url(selected_feed, page_number, per_page),
+        viewer.as_ref(),
     )
+    .cache(request::cache::ARTICLES_TTL_MS)
+    .fetch_json_data(move |data_result: ResponseDataResult<RootDecoder>| {
+        f(data_result
+            .map(move |root_decoder| root_decoder.into_paginated_list(&viewer, per_page))
+            .map_err(request::fail_reason_into_api_error))
+    })
 }
@@ -1,6 +1,6 @@
 use crate::{
     coder::decoder,
-    entity::{Article, ErrorMessage, PageNumber, PaginatedList, Username, Viewer},
+    entity::{ApiError, Article, PageNumber, PaginatedList, Username, Viewer},
     logger,
     page::profile::SelectedFeed,
     request,
@@ -11,7 +11,7 @@ use serde::Deserialize;
 use std::{borrow::Cow, num::NonZeroUsize};
 
 lazy_static! {
-    static ref ARTICLES_PER_PAGE: NonZeroUsize = NonZeroUsize::new(5).unwrap();
+    pub static ref ARTICLES_PER_PAGE: NonZeroUsize = NonZeroUsize::new(5).unwrap();
 }
 
 #[derive(Deserialize, Debug)]
@@ -22,7 +22,11 @@ struct RootDecoder {
 }
 
 impl RootDecoder {
-    fn into_paginated_list(self, viewer: Option<&Viewer>) -> PaginatedList<Article> {
+    fn into_paginated_list(
+        self,
+        viewer: Option<&Viewer>,
+        per_page: NonZeroUsize,
+    ) -> PaginatedList<Article> {
         PaginatedList {
             items: self
                 .articles
@@ -37,7 +41,7 @@ impl RootDecoder {
                     }
                 })
                 .collect(),
-            per_page: *ARTICLES_PER_PAGE,
+            per_page,
             total: self.articles_count,
         }
     }
@@ -47,6 +51,7 @@ pub fn request_url(
     username: &Username<'static>,
     selected_feed: SelectedFeed,
     page_number: PageNumber,
+    per_page: NonZeroUsize,
 ) -> String {
     format!(
         "articles?{}={}&limit={}&offset={}",
@@ -55,8 +60,8 @@ pub fn request_url(
             SelectedFeed::FavoritedArticles => "favorited",
         },
         username.as_str(),
-        *ARTICLES_PER_PAGE,
-        (*page_number - 1) * ARTICLES_PER_PAGE.get()
+        per_page,
+        (*page_number - 1) * per_page.get()
     )
 }
 
@@ -66,17 +71,19 @@ pub async fn load_for_profile<Ms: 'static>(
     username: Username<'static>,
     selected_feed: SelectedFeed,
     page_number: PageNumber,
-    f: fn(Result<PaginatedList<Article>, (Username<'static>, Vec<ErrorMessage>)>) -> Ms,
+    per_page: NonZeroUsize,
+    f: impl FnOnce(Result<PaginatedList<Article>, (Username<'static>, ApiError)>) -> Ms + 'static,
 ) -> Result<Ms, Ms> {
     request::new(
-        &request_url(&username, selected_feed, page_number),
+        &request_url(&username, selected_feed, page_number, per_page),
         viewer.as_ref(),
     )
+    .cache(request::cache::ARTICLES_TTL_MS)
     .fetch_json_data(move |data_result: ResponseDataResult<RootDecoder>| {
         f(data_result
-            .map(move |root_decoder| root_decoder.into_paginated_list(viewer.as_ref()))
-            .map_err(request::fail_reason_into_errors)
-            .map_err(|errors| (username, errors)))
+            .map(move |root_decoder| root_decoder.into_paginated_list(viewer.as_ref(), per_page))
+            .map_err(request::fail_reason_into_api_error)
+            .map_err(|error| (username, error)))
     })
     .await
 }
@@ -1,5 +1,5 @@
 mod load_for_profile;
-pub use load_for_profile::load_for_profile;
+pub use load_for_profile::{load_for_profile, ARTICLES_PER_PAGE as PROFILE_ARTICLES_PER_PAGE};
 
 mod load_for_home;
-pub use load_for_home::load_for_home;
+pub use load_for_home::{load_for_home, ARTICLES_PER_PAGE as HOME_ARTICLES_PER_PAGE};
@@ -1,6 +1,6 @@
 use crate::{
     coder::decoder,
-    entity::{username, Author, ErrorMessage, Viewer},
+    entity::{username, ApiError, Author, Viewer},
     request,
 };
 use seed::fetch::{Method, ResponseDataResult};
@@ -16,16 +16,18 @@ struct RootDecoder {
 pub fn follow<Ms: 'static>(
     viewer: Option<Viewer>,
     username: &username::Username<'_>,
-    f: fn(Result<Author, Vec<ErrorMessage>>) -> Ms,
+    f: fn(Result<Author, ApiError>) -> Ms,
 ) -> impl Future<Output = Result<Ms, Ms>> {
     request::new(
         &format!("profiles/{}/follow", username.as_str()),
         viewer.as_ref(),
     )
     .method(Method::Post)
+    .invalidate("profiles")
+    .invalidate("articles")
     .fetch_json_data(move |data_result: ResponseDataResult<RootDecoder>| {
         f(data_result
             .map(move |root_decoder| root_decoder.profile.into_author(viewer.map(Cow::Owned)))
-            .map_err(request::fail_reason_into_errors))
+            .map_err(request::fail_reason_into_api_error))
     })
 }
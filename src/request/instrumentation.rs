@@ -0,0 +1,179 @@
+use crate::logger;
+use serde::Serialize;
+use std::{cell::RefCell, collections::VecDeque, fmt, rc::Rc};
+
+pub const RING_BUFFER_CAPACITY: usize = 100;
+
+// ------ RequestRecord ------
+
+/// Description of one finished API call (one attempt of a retried request).
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RequestRecord {
+    pub method: String,
+    /// Path relative to the base API URL (e.g. `articles/feed?limit=10&offset=0`).
+    pub path: String,
+    /// `None` for network errors, timeouts and cache hits.
+    pub status: Option<u16>,
+    pub attempt: u32,
+    pub duration_ms: f64,
+    /// Size of the response body in bytes.
+    pub payload_size: usize,
+    /// The response has been served from the cache, no request has been sent.
+    pub from_cache: bool,
+}
+
+impl fmt::Display for RequestRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let status = match self.status {
+            _ if self.from_cache => "cache".to_owned(),
+            Some(status) => status.to_string(),
+            None => "failed".to_owned(),
+        };
+        write!(
+            f,
+            "{} /{} {} {:.0}ms {}B",
+            self.method, self.path, status, self.duration_ms, self.payload_size
+        )?;
+        if self.attempt > 1 {
+            write!(f, " (attempt {})", self.attempt)?;
+        }
+        Ok(())
+    }
+}
+
+// ------ Sink ------
+
+/// Receiver of request records.
+pub trait Sink {
+    fn record(&self, record: &RequestRecord);
+}
+
+thread_local! {
+    static SINKS: RefCell<Vec<Rc<dyn Sink>>> = RefCell::new(Vec::new());
+    static RING_BUFFER: Rc<RingBuffer> = Rc::new(RingBuffer::new(RING_BUFFER_CAPACITY));
+}
+
+/// Register the default sinks - the ring buffer and, in debug builds, the console.
+pub fn init() {
+    add_sink(RING_BUFFER.with(|ring_buffer| Rc::clone(ring_buffer) as Rc<dyn Sink>));
+    if cfg!(debug_assertions) {
+        add_sink(Rc::new(ConsoleSink));
+    }
+}
+
+/// Records from the default ring buffer.
+pub fn recent_records() -> Vec<RequestRecord> {
+    RING_BUFFER.with(|ring_buffer| ring_buffer.records())
+}
+
+pub fn add_sink(sink: Rc<dyn Sink>) {
+    SINKS.with(|sinks| sinks.borrow_mut().push(sink));
+}
+
+/// Send the record to all registered sinks.
+pub fn record(record: &RequestRecord) {
+    // Clone sinks so they can register other sinks.
+    let sinks = SINKS.with(|sinks| sinks.borrow().clone());
+    for sink in sinks {
+        sink.record(record);
+    }
+}
+
+// ------ ConsoleSink ------
+
+/// Writes records to the browser console.
+pub struct ConsoleSink;
+
+impl Sink for ConsoleSink {
+    fn record(&self, record: &RequestRecord) {
+        logger::info(record)
+    }
+}
+
+// ------ RingBuffer ------
+
+/// Keeps the last `capacity` records in memory.
+pub struct RingBuffer {
+    capacity: usize,
+    records: RefCell<VecDeque<RequestRecord>>,
+}
+
+impl RingBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            records: RefCell::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    /// Records from the oldest one.
+    pub fn records(&self) -> Vec<RequestRecord> {
+        self.records.borrow().iter().cloned().collect()
+    }
+}
+
+impl Sink for RingBuffer {
+    fn record(&self, record: &RequestRecord) {
+        let mut records = self.records.borrow_mut();
+        if records.len() == self.capacity {
+            records.pop_front();
+        }
+        if self.capacity > 0 {
+            records.push_back(record.clone());
+        }
+    }
+}
+
+// ====== ====== TESTS ====== ======
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn request_record(path: &str) -> RequestRecord {
+        RequestRecord {
+            method: "GET".into(),
+            path: path.into(),
+            status: Some(200),
+            attempt: 1,
+            duration_ms: 12.,
+            payload_size: 42,
+            from_cache: false,
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn ring_buffer_drops_oldest_records_test() {
+        // ====== ARRANGE ======
+        let ring_buffer = RingBuffer::new(2);
+
+        // ====== ACT ======
+        ring_buffer.record(&request_record("tags"));
+        ring_buffer.record(&request_record("articles"));
+        ring_buffer.record(&request_record("profiles/jake"));
+
+        // ====== ASSERT ======
+        let paths = ring_buffer
+            .records()
+            .into_iter()
+            .map(|record| record.path)
+            .collect::<Vec<_>>();
+        assert_eq!(paths, vec!["articles", "profiles/jake"]);
+    }
+
+    #[wasm_bindgen_test]
+    fn request_record_display_test() {
+        // ====== ARRANGE ======
+        let mut record = request_record("tags");
+        record.attempt = 2;
+
+        // ====== ACT ======
+        let displayed = record.to_string();
+
+        // ====== ASSERT ======
+        assert_eq!(displayed, "GET /tags 200 12ms 42B (attempt 2)");
+    }
+}
@@ -1,9 +1,6 @@
 use crate::{
     coder::decoder,
-    entity::{
-        form::login::{Problem, ValidForm},
-        Viewer,
-    },
+    entity::{form::login::ValidForm, ApiError, Viewer},
     request,
 };
 use seed::fetch::{Method, ResponseDataResult};
@@ -19,7 +16,7 @@ struct RootDecoder {
 
 pub fn login<Ms: 'static>(
     valid_form: &ValidForm,
-    f: fn(Result<Viewer, Vec<Problem>>) -> Ms,
+    f: fn(Result<Viewer, ApiError>) -> Ms,
 ) -> impl Future<Output = Result<Ms, Ms>> {
     request::new("users/login", None)
         .method(Method::Post)
@@ -27,6 +24,6 @@ pub fn login<Ms: 'static>(
         .fetch_json_data(move |data_result: ResponseDataResult<RootDecoder>| {
             f(data_result
                 .map(|root_decoder| root_decoder.user.into_viewer())
-                .map_err(request::fail_reason_into_problems))
+                .map_err(request::fail_reason_into_api_error))
         })
 }
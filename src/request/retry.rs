@@ -0,0 +1,119 @@
+use std::cmp;
+
+// ------ RetryPolicy ------
+
+/// How many times and how long to wait before an idempotent request is sent again.
+#[derive(Copy, Clone, Debug)]
+pub struct RetryPolicy {
+    /// The first attempt included.
+    pub max_attempts: u32,
+    pub base_delay_ms: u32,
+    /// Longer `Retry-After` values aren't waited for - the request fails instead.
+    pub max_delay_ms: u32,
+}
+
+impl RetryPolicy {
+    /// Send the request only once.
+    pub const NONE: Self = Self {
+        max_attempts: 1,
+        base_delay_ms: 0,
+        max_delay_ms: 0,
+    };
+
+    pub fn can_retry(&self, attempt: u32) -> bool {
+        attempt < self.max_attempts
+    }
+
+    /// Delay before the attempt following `attempt` (counted from 1).
+    ///
+    /// `Retry-After` wins if it's present, otherwise exponential backoff with jitter is used.
+    /// `jitter` is a random number from the range `0..1`.
+    pub fn delay_ms(&self, attempt: u32, retry_after_ms: Option<u32>, jitter: f64) -> Option<u32> {
+        match retry_after_ms {
+            Some(retry_after_ms) if retry_after_ms > self.max_delay_ms => None,
+            Some(retry_after_ms) => Some(retry_after_ms),
+            None => {
+                let exponent = cmp::min(attempt.saturating_sub(1), 16);
+                let backoff = cmp::min(
+                    self.base_delay_ms.saturating_mul(2_u32.pow(exponent)),
+                    self.max_delay_ms,
+                );
+                // "Equal jitter" - wait at least half of the backoff.
+                let half_backoff = f64::from(backoff) / 2.;
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                Some((half_backoff + half_backoff * jitter.clamp(0., 1.)) as u32)
+            }
+        }
+    }
+}
+
+pub fn is_retryable_status(status_code: u16) -> bool {
+    matches!(status_code, 408 | 429 | 500 | 502 | 503 | 504)
+}
+
+/// Only 429 and 503 responses are expected to contain a meaningful `Retry-After`.
+pub fn honours_retry_after(status_code: u16) -> bool {
+    status_code == 429 || status_code == 503
+}
+
+/// Parse `Retry-After` value in seconds (e.g. "120") into milliseconds.
+/// The HTTP-date format isn't supported.
+pub fn parse_retry_after(value: &str) -> Option<u32> {
+    value
+        .trim()
+        .parse::<u32>()
+        .ok()
+        .map(|seconds| seconds.saturating_mul(1000))
+}
+
+// ====== ====== TESTS ====== ======
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    const POLICY: RetryPolicy = RetryPolicy {
+        max_attempts: 3,
+        base_delay_ms: 200,
+        max_delay_ms: 1000,
+    };
+
+    #[wasm_bindgen_test]
+    fn can_retry_test() {
+        // ====== ACT & ASSERT ======
+        assert!(POLICY.can_retry(1));
+        assert!(POLICY.can_retry(2));
+        assert!(!POLICY.can_retry(3));
+    }
+
+    #[wasm_bindgen_test]
+    fn exponential_backoff_test() {
+        // ====== ACT ======
+        let delays = (1..=4)
+            .map(|attempt| POLICY.delay_ms(attempt, None, 1.))
+            .collect::<Vec<_>>();
+
+        // ====== ASSERT ======
+        assert_eq!(delays, vec![Some(200), Some(400), Some(800), Some(1000)]);
+    }
+
+    #[wasm_bindgen_test]
+    fn backoff_jitter_test() {
+        // ====== ACT ======
+        let delay = POLICY.delay_ms(2, None, 0.);
+
+        // ====== ASSERT ======
+        assert_eq!(delay, Some(200));
+    }
+
+    #[wasm_bindgen_test]
+    fn retry_after_test() {
+        // ====== ACT & ASSERT ======
+        assert_eq!(POLICY.delay_ms(1, parse_retry_after("1"), 0.5), Some(1000));
+        assert_eq!(POLICY.delay_ms(1, parse_retry_after("120"), 0.5), None);
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), None);
+    }
+}
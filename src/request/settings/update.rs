@@ -1,9 +1,6 @@
 use crate::{
     coder::decoder,
-    entity::{
-        form::settings::{Problem, ValidForm},
-        Viewer,
-    },
+    entity::{form::settings::ValidForm, ApiError, Viewer},
     request,
 };
 use seed::fetch::{Method, ResponseDataResult};
@@ -19,14 +16,16 @@ struct RootDecoder {
 pub fn update<Ms: 'static>(
     viewer: Option<&Viewer>,
     valid_form: &ValidForm,
-    f: fn(Result<Viewer, Vec<Problem>>) -> Ms,
+    f: fn(Result<Viewer, ApiError>) -> Ms,
 ) -> impl Future<Output = Result<Ms, Ms>> {
     request::new("user", viewer)
         .method(Method::Put)
+        .invalidate("profiles")
+        .invalidate("articles")
         .send_json(&valid_form.to_encoder())
         .fetch_json_data(move |data_result: ResponseDataResult<RootDecoder>| {
             f(data_result
                 .map(|root_decoder| root_decoder.user.into_viewer())
-                .map_err(request::fail_reason_into_problems))
+                .map_err(request::fail_reason_into_api_error))
         })
 }
@@ -1,7 +1,7 @@
 use crate::{
     entity::{
         article::tag::{IntoTags, Tag},
-        ErrorMessage,
+        ApiError,
     },
     request,
 };
@@ -14,14 +14,13 @@ struct RootDecoder {
     tags: Vec<String>,
 }
 
-pub async fn load_list<Ms: 'static>(
-    f: fn(Result<Vec<Tag>, Vec<ErrorMessage>>) -> Ms,
-) -> Result<Ms, Ms> {
+pub async fn load_list<Ms: 'static>(f: fn(Result<Vec<Tag>, ApiError>) -> Ms) -> Result<Ms, Ms> {
     request::new("tags", None)
+        .cache(request::cache::TAGS_TTL_MS)
         .fetch_json_data(move |data_result: ResponseDataResult<RootDecoder>| {
             f(data_result
                 .map(|root_decoder| root_decoder.tags.into_tags())
-                .map_err(request::fail_reason_into_errors))
+                .map_err(request::fail_reason_into_api_error))
         })
         .await
 }
@@ -0,0 +1,29 @@
+use crate::{
+    coder::decoder,
+    entity::{ApiError, Viewer},
+    request::{self, retry::RetryPolicy},
+};
+use seed::fetch::ResponseDataResult;
+use serde::Deserialize;
+use std::future::Future;
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct RootDecoder {
+    user: decoder::Viewer,
+}
+
+/// Load the current state of the viewer identified by the auth token.
+pub fn load<Ms: 'static>(
+    viewer: &Viewer,
+    f: fn(Result<Viewer, ApiError>) -> Ms,
+) -> impl Future<Output = Result<Ms, Ms>> {
+    request::new("user", Some(viewer))
+        // The stored viewer is used when the refresh fails.
+        .retry_policy(RetryPolicy::NONE)
+        .fetch_json_data(move |data_result: ResponseDataResult<RootDecoder>| {
+            f(data_result
+                .map(|root_decoder| root_decoder.user.into_viewer())
+                .map_err(request::fail_reason_into_api_error))
+        })
+}
@@ -1,59 +1,254 @@
-use std::{borrow::Cow, convert::TryFrom, fmt};
+use std::{borrow::Cow, cell::RefCell, convert::TryFrom, fmt};
 
 use seed::prelude::*;
 
 use crate::{
-    entity::{Slug, Username},
+    entity::{preferences::HomeFeed, PageNumber, Slug, Tag, Username},
     GMsg,
 };
 
+static MODE_META_NAME: &str = "conduit-routing";
+
+thread_local! {
+    static CONFIG: RefCell<Config> = const {
+        RefCell::new(Config {
+            mode: Mode::Path,
+            base_path: String::new(),
+        })
+    };
+    /// The URL fragment of the last location handled in the hash mode.
+    static LAST_HASH: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
 pub fn go_to<Ms: 'static>(route: Route<'static>, orders: &mut impl Orders<Ms, GMsg>) {
-    seed::push_route(route.clone());
+    push(route.clone());
     orders.send_g_msg(GMsg::RoutePushed(route));
 }
 
+/// Change the URL without opening the route - e.g. to make the page state bookmarkable.
+pub fn push(route: Route) {
+    let url = seed::push_route(route.to_url(&config()));
+    LAST_HASH.with(|last_hash| *last_hash.borrow_mut() = url.hash);
+}
+
+/// Both `popstate` and `hashchange` are fired when the URL fragment changes,
+/// so the same location has to be ignored in the hash mode.
+pub fn is_new_location(url: &seed::Url) -> bool {
+    match mode() {
+        Mode::Path => true,
+        Mode::Hash => LAST_HASH.with(|last_hash| last_hash.replace(url.hash.clone()) != url.hash),
+    }
+}
+
+// ------ Config ------
+
+/// How routes are represented in URLs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Config {
+    pub mode: Mode,
+    /// The path the app is served from without the trailing slash - e.g. `/apps/conduit`;
+    /// empty at the domain root.
+    pub base_path: String,
+}
+
+impl Config {
+    fn base_path_segments(&self) -> impl Iterator<Item = &str> {
+        self.base_path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+    }
+}
+
+/// Select the mode by `<meta name="conduit-routing" content="hash">`
+/// and the base path by `<base href="/apps/conduit/">` in `index.html`.
+pub fn init() {
+    let document = seed::document();
+    let mode = document
+        .query_selector(&format!("meta[name='{}']", MODE_META_NAME))
+        .ok()
+        .flatten()
+        .and_then(|meta| meta.get_attribute("content"))
+        .map_or(Mode::Path, |content| match content.trim() {
+            "hash" => Mode::Hash,
+            _ => Mode::Path,
+        });
+    // `baseURI` is the document URL when there is no `base` element.
+    let base_path = document
+        .query_selector("base[href]")
+        .ok()
+        .flatten()
+        .and_then(|_| document.base_uri().ok().flatten())
+        .and_then(|base_uri| web_sys::Url::new(&base_uri).ok())
+        .map(|base_url| base_url.pathname())
+        .unwrap_or_default();
+    set_config(Config {
+        mode,
+        base_path: base_path.trim_end_matches('/').to_owned(),
+    });
+}
+
+pub fn config() -> Config {
+    CONFIG.with(|config| config.borrow().clone())
+}
+
+pub fn set_config(config: Config) {
+    CONFIG.with(|old_config| *old_config.borrow_mut() = config);
+}
+
+pub fn mode() -> Mode {
+    CONFIG.with(|config| config.borrow().mode)
+}
+
+/// Prefix of app URLs - e.g. `/apps/conduit`; empty at the domain root.
+pub fn base_path() -> String {
+    CONFIG.with(|config| config.borrow().base_path.clone())
+}
+
+// ------ Mode ------
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Mode {
+    /// `/article/foo` - the server has to respond with `index.html` to all paths.
+    #[default]
+    Path,
+    /// `/#/article/foo` - for static hosts without rewrite rules.
+    Hash,
+}
+
 // ------ Route ------
 
 #[derive(Clone, Debug)]
 pub enum Route<'a> {
-    Home,
+    Home(HomeQuery),
     Root,
-    Login,
+    /// Contains the route to open after the viewer signs in - e.g. `/login?redirect=%2Fsettings`.
+    Login(Option<Box<Route<'a>>>),
     Logout,
-    Register,
+    /// Contains the route to open after the viewer signs up.
+    Register(Option<Box<Route<'a>>>),
     Settings,
     Article(Slug),
     Profile(Cow<'a, Username<'a>>),
+    /// Articles favorited by the user.
+    ProfileFavorites(Cow<'a, Username<'a>>),
     NewArticle,
     EditArticle(Slug),
+    Drafts,
+    /// Draft of a new article with the given id.
+    Draft(String),
 }
 
 impl<'a> Route<'a> {
     pub fn path(&self) -> Vec<&str> {
         use Route::*;
         match self {
-            Home | Root => vec![],
-            Login => vec!["login"],
+            Home(_) | Root => vec![],
+            Login(_) => vec!["login"],
             Logout => vec!["logout"],
-            Register => vec!["register"],
+            Register(_) => vec!["register"],
             Settings => vec!["settings"],
             Article(slug) => vec!["article", slug.as_str()],
             Profile(username) => vec!["profile", username.as_str()],
+            ProfileFavorites(username) => vec!["profile", username.as_str(), "favorites"],
             NewArticle => vec!["editor"],
             EditArticle(slug) => vec!["editor", slug.as_str()],
+            Drafts => vec!["drafts"],
+            Draft(id) => vec!["drafts", id.as_str()],
+        }
+    }
+
+    /// Query parameters without the leading `?`; `None` if there are no parameters.
+    pub fn search(&self) -> Option<String> {
+        match self {
+            Route::Home(query) => query_string(&query.params()),
+            Route::Login(Some(redirect)) | Route::Register(Some(redirect)) => {
+                query_string(&[("redirect", redirect.relative_url())])
+            }
+            _ => None,
+        }
+    }
+
+    /// The route relative to the app root - e.g. `/article/foo` or `/?tag=rust`.
+    fn relative_url(&self) -> String {
+        let path = format!("/{}", self.path().join("/"));
+        match self.search() {
+            Some(search) => format!("{}?{}", path, search),
+            None => path,
+        }
+    }
+
+    fn href(&self, config: &Config) -> String {
+        match config.mode {
+            Mode::Path => format!("{}{}", config.base_path, self.relative_url()),
+            Mode::Hash => format!("{}/#{}", config.base_path, self.relative_url()),
+        }
+    }
+
+    fn to_url(&self, config: &Config) -> seed::Url {
+        let mut path = config.base_path_segments().collect::<Vec<_>>();
+        match config.mode {
+            Mode::Path => {
+                path.extend(self.path());
+                if self.path().is_empty() {
+                    // Keep the trailing slash of the base path.
+                    path.push("");
+                }
+                let url = seed::Url::new(path);
+                match self.search() {
+                    Some(search) => url.search(&search),
+                    None => url,
+                }
+            }
+            Mode::Hash => {
+                path.push("");
+                seed::Url::new(path).hash(&self.relative_url())
+            }
+        }
+    }
+
+    /// Guests are redirected to the login page.
+    ///
+    /// `Settings` are open to guests - e.g. to switch the backend before they sign in.
+    pub fn requires_viewer(&self) -> bool {
+        use Route::*;
+        matches!(self, NewArticle | EditArticle(_) | Drafts | Draft(_))
+    }
+
+    pub fn to_static(&self) -> Route<'static> {
+        use Route::*;
+        let to_static = |redirect: &Option<Box<Route>>| {
+            redirect
+                .as_ref()
+                .map(|redirect| Box::new(redirect.to_static()))
+        };
+        match self {
+            Home(query) => Home(query.clone()),
+            Root => Root,
+            Login(redirect) => Login(to_static(redirect)),
+            Logout => Logout,
+            Register(redirect) => Register(to_static(redirect)),
+            Settings => Settings,
+            Article(slug) => Article(slug.clone()),
+            Profile(username) => Profile(Cow::Owned(username.to_static())),
+            ProfileFavorites(username) => ProfileFavorites(Cow::Owned(username.to_static())),
+            NewArticle => NewArticle,
+            EditArticle(slug) => EditArticle(slug.clone()),
+            Drafts => Drafts,
+            Draft(id) => Draft(id.clone()),
         }
     }
 }
 
+/// `href` of links to the route.
 impl<'a> fmt::Display for Route<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "/{}", self.path().join("/"))
+        write!(f, "{}", self.href(&config()))
     }
 }
 
 impl<'a> From<Route<'a>> for seed::Url {
     fn from(route: Route) -> Self {
-        route.path().into()
+        route.to_url(&config())
     }
 }
 
@@ -61,35 +256,177 @@ impl<'a> TryFrom<seed::Url> for Route<'a> {
     type Error = ();
 
     fn try_from(url: seed::Url) -> Result<Self, Self::Error> {
-        let mut path = url.path.into_iter();
-
-        match path.next().as_ref().map(String::as_str) {
-            None | Some("") => Some(Route::Home),
-            Some("login") => Some(Route::Login),
-            Some("logout") => Some(Route::Logout),
-            Some("settings") => Some(Route::Settings),
-            Some("profile") => path
+        parse(url, &config())
+    }
+}
+
+// ------ HomeQuery ------
+
+/// Bookmarkable state of the home page - e.g. `/?tag=rust&page=3` or `/?feed=your`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HomeQuery {
+    /// `None` - the feed chosen in the viewer's preferences.
+    pub feed: Option<HomeFeed>,
+    /// The tag feed is selected; `feed` is ignored.
+    pub tag: Option<Tag>,
+    pub page: PageNumber,
+}
+
+impl HomeQuery {
+    fn from_params(params: &[(String, String)]) -> Self {
+        let mut query = Self::default();
+        for (name, value) in params {
+            match name.as_str() {
+                "feed" => {
+                    query.feed = match value.as_str() {
+                        "your" => Some(HomeFeed::Your),
+                        "global" => Some(HomeFeed::Global),
+                        _ => None,
+                    }
+                }
+                "tag" if !value.is_empty() => query.tag = Some(Tag::from(value.clone())),
+                "page" => {
+                    query.page = value
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|page| *page > 0)
+                        .map(PageNumber::from)
+                        .unwrap_or_default()
+                }
+                _ => (),
+            }
+        }
+        query
+    }
+
+    fn params(&self) -> Vec<(&str, String)> {
+        let mut params = Vec::new();
+        match (&self.tag, self.feed) {
+            (Some(tag), _) => params.push(("tag", tag.to_string())),
+            (None, Some(HomeFeed::Your)) => params.push(("feed", "your".to_owned())),
+            (None, Some(HomeFeed::Global)) => params.push(("feed", "global".to_owned())),
+            (None, None) => (),
+        }
+        if self.page != PageNumber::default() {
+            params.push(("page", self.page.to_string()));
+        }
+        params
+    }
+}
+
+// ====== PRIVATE ======
+
+fn parse<'a>(url: seed::Url, config: &Config) -> Result<Route<'a>, ()> {
+    let (path, search) = match config.mode {
+        Mode::Path => {
+            let mut path = url.path.into_iter();
+            // URLs outside of the base path don't belong to the app.
+            for segment in config.base_path_segments() {
+                if path.next().as_deref() != Some(segment) {
+                    return Err(());
+                }
+            }
+            (path.collect::<Vec<_>>(), url.search)
+        }
+        Mode::Hash => {
+            let hash = url.hash.unwrap_or_default();
+            let mut parts = hash.splitn(2, '?');
+            let path = parts
+                .next()
+                .unwrap_or_default()
+                .trim_start_matches('/')
+                .split('/')
+                .map(ToOwned::to_owned)
+                .collect();
+            (path, parts.next().map(ToOwned::to_owned))
+        }
+    };
+    let mut path = path.into_iter();
+    let params = query_params(search.as_deref());
+
+    match path.next().as_ref().map(String::as_str) {
+        None | Some("") => Some(Route::Home(HomeQuery::from_params(&params))),
+        Some("login") => Some(Route::Login(redirect_param(&params))),
+        Some("logout") => Some(Route::Logout),
+        Some("settings") => Some(Route::Settings),
+        Some("profile") => {
+            let username = path
                 .next()
                 .filter(|username| !username.is_empty())
                 .map(Username::from)
-                .map(Cow::Owned)
-                .map(Route::Profile),
-            Some("register") => Some(Route::Register),
-            Some("article") => path
-                .next()
-                .filter(|slug| !slug.is_empty())
-                .map(Slug::from)
-                .map(Route::Article),
-            Some("editor") => path
-                .next()
-                .filter(|slug| !slug.is_empty())
-                .map(Slug::from)
-                .map(Route::EditArticle)
-                .or_else(|| Some(Route::NewArticle)),
-            _ => None,
+                .map(Cow::Owned);
+            match path.next().as_deref() {
+                None | Some("") => username.map(Route::Profile),
+                Some("favorites") => username.map(Route::ProfileFavorites),
+                _ => None,
+            }
         }
-        .ok_or(())
+        Some("register") => Some(Route::Register(redirect_param(&params))),
+        Some("article") => path
+            .next()
+            .filter(|slug| !slug.is_empty())
+            .map(Slug::from)
+            .map(Route::Article),
+        Some("editor") => path
+            .next()
+            .filter(|slug| !slug.is_empty())
+            .map(Slug::from)
+            .map(Route::EditArticle)
+            .or_else(|| Some(Route::NewArticle)),
+        Some("drafts") => match path.next() {
+            None => Some(Route::Drafts),
+            Some(id) if id.is_empty() => Some(Route::Drafts),
+            Some(id) => Some(Route::Draft(id)),
+        },
+        _ => None,
     }
+    .ok_or(())
+}
+
+/// Decoded `name=value` pairs of the search part of the URL (without the leading `?`).
+fn query_params(search: Option<&str>) -> Vec<(String, String)> {
+    let decode = |component: &str| {
+        js_sys::decode_uri_component(&component.replace('+', " "))
+            .ok()
+            .and_then(|decoded| decoded.as_string())
+    };
+    search
+        .unwrap_or_default()
+        .split('&')
+        .filter(|param| !param.is_empty())
+        .filter_map(|param| {
+            let mut parts = param.splitn(2, '=');
+            let name = decode(parts.next()?)?;
+            let value = decode(parts.next().unwrap_or_default())?;
+            Some((name, value))
+        })
+        .collect()
+}
+
+/// Only routes of this app are accepted, so the login page can't send the viewer to another site.
+fn redirect_param<'a>(params: &[(String, String)]) -> Option<Box<Route<'a>>> {
+    let (_, redirect) = params.iter().find(|(name, _)| name == "redirect")?;
+    if !redirect.starts_with('/') || redirect.starts_with("//") {
+        return None;
+    }
+    let url = seed::Url::try_from(redirect.clone()).ok()?;
+    match parse(url, &Config::default()).ok()? {
+        Route::Login(_) | Route::Register(_) | Route::Logout => None,
+        route => Some(Box::new(route)),
+    }
+}
+
+fn query_string(params: &[(&str, String)]) -> Option<String> {
+    if params.is_empty() {
+        return None;
+    }
+    Some(
+        params
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, js_sys::encode_uri_component(value)))
+            .collect::<Vec<_>>()
+            .join("&"),
+    )
 }
 
 // ====== ====== TESTS ====== ======
@@ -111,8 +448,8 @@ pub mod tests {
         let route = url.try_into();
 
         // ====== ASSERT ======
-        assert!(if let Ok(Route::Home) = route {
-            true
+        assert!(if let Ok(Route::Home(query)) = route {
+            query == HomeQuery::default()
         } else {
             false
         })
@@ -127,13 +464,64 @@ pub mod tests {
         let route = url.try_into();
 
         // ====== ASSERT ======
-        assert!(if let Ok(Route::Home) = route {
-            true
+        assert!(if let Ok(Route::Home(query)) = route {
+            query == HomeQuery::default()
+        } else {
+            false
+        })
+    }
+
+    #[wasm_bindgen_test]
+    fn home_route_query_test() {
+        // ====== ARRANGE ======
+        let url = seed::Url::new(vec![""]).search("tag=rust%20lang&page=3&unknown=1");
+
+        // ====== ACT ======
+        let route = url.try_into();
+
+        // ====== ASSERT ======
+        assert!(if let Ok(Route::Home(query)) = route {
+            query
+                == HomeQuery {
+                    feed: None,
+                    tag: Some(Tag::from("rust lang".to_owned())),
+                    page: PageNumber::from(3),
+                }
         } else {
             false
         })
     }
 
+    #[wasm_bindgen_test]
+    fn home_route_invalid_query_test() {
+        // ====== ARRANGE ======
+        let url = seed::Url::new(vec![""]).search("feed=unknown&page=0");
+
+        // ====== ACT ======
+        let route = url.try_into();
+
+        // ====== ASSERT ======
+        assert!(if let Ok(Route::Home(query)) = route {
+            query == HomeQuery::default()
+        } else {
+            false
+        })
+    }
+
+    #[wasm_bindgen_test]
+    fn home_route_display_test() {
+        // ====== ARRANGE ======
+        let route = Route::Home(HomeQuery {
+            feed: Some(HomeFeed::Your),
+            tag: None,
+            page: PageNumber::from(2),
+        });
+
+        // ====== ACT & ASSERT ======
+        assert_eq!(route.to_string(), "/?feed=your&page=2");
+        assert_eq!(Route::Home(HomeQuery::default()).to_string(), "/");
+    }
+
     #[wasm_bindgen_test]
     fn login_route_test() {
         // ====== ARRANGE ======
@@ -143,13 +531,45 @@ pub mod tests {
         let route = url.try_into();
 
         // ====== ASSERT ======
-        assert!(if let Ok(Route::Login) = route {
+        assert!(if let Ok(Route::Login(None)) = route {
             true
         } else {
             false
         })
     }
 
+    #[wasm_bindgen_test]
+    fn login_route_redirect_test() {
+        // ====== ARRANGE ======
+        let route = Route::Login(Some(Box::new(Route::EditArticle(Slug::from(
+            "my_article".to_owned(),
+        )))));
+
+        // ====== ACT ======
+        let url = seed::Url::from(route.clone());
+        let parsed_route = url.try_into();
+
+        // ====== ASSERT ======
+        assert_eq!(route.to_string(), "/login?redirect=%2Feditor%2Fmy_article");
+        assert!(if let Ok(Route::Login(Some(redirect))) = parsed_route {
+            matches!(*redirect, Route::EditArticle(slug) if slug.as_str() == "my_article")
+        } else {
+            false
+        })
+    }
+
+    #[wasm_bindgen_test]
+    fn login_route_external_redirect_test() {
+        // ====== ARRANGE ======
+        let url = seed::Url::new(vec!["login"]).search("redirect=https%3A%2F%2Fexample.com%2F");
+
+        // ====== ACT ======
+        let route = url.try_into();
+
+        // ====== ASSERT ======
+        assert!(matches!(route, Ok(Route::Login(None))))
+    }
+
     #[wasm_bindgen_test]
     fn logout_route_test() {
         // ====== ARRANGE ======
@@ -198,6 +618,22 @@ pub mod tests {
         })
     }
 
+    #[wasm_bindgen_test]
+    fn profile_favorites_route_test() {
+        // ====== ARRANGE ======
+        let url = seed::Url::new(vec!["profile", "john", "favorites"]);
+
+        // ====== ACT ======
+        let route = url.try_into();
+
+        // ====== ASSERT ======
+        assert!(if let Ok(Route::ProfileFavorites(username)) = route {
+            username.as_str() == "john"
+        } else {
+            false
+        })
+    }
+
     #[wasm_bindgen_test]
     fn register_route_test() {
         // ====== ARRANGE ======
@@ -207,7 +643,7 @@ pub mod tests {
         let route = url.try_into();
 
         // ====== ASSERT ======
-        assert!(if let Ok(Route::Register) = route {
+        assert!(if let Ok(Route::Register(None)) = route {
             true
         } else {
             false
@@ -266,6 +702,168 @@ pub mod tests {
         )
     }
 
+    #[wasm_bindgen_test]
+    fn drafts_route_test() {
+        // ====== ARRANGE ======
+        let url = seed::Url::new(vec!["drafts"]);
+
+        // ====== ACT ======
+        let route = url.try_into();
+
+        // ====== ASSERT ======
+        assert!(matches!(route, Ok(Route::Drafts)))
+    }
+
+    #[wasm_bindgen_test]
+    fn draft_route_test() {
+        // ====== ARRANGE ======
+        let url = seed::Url::new(vec!["drafts", "1578441600000"]);
+
+        // ====== ACT ======
+        let route = url.try_into();
+
+        // ====== ASSERT ======
+        assert!(if let Ok(Route::Draft(id)) = route {
+            id == "1578441600000"
+        } else {
+            false
+        })
+    }
+
+    #[wasm_bindgen_test]
+    fn hash_mode_route_test() {
+        // ====== ARRANGE ======
+        let url = seed::Url::new(vec![""]).hash("/profile/john/favorites");
+        let config = Config {
+            mode: Mode::Hash,
+            base_path: String::new(),
+        };
+
+        // ====== ACT ======
+        let route = parse(url, &config);
+
+        // ====== ASSERT ======
+        assert!(if let Ok(Route::ProfileFavorites(username)) = route {
+            username.as_str() == "john"
+        } else {
+            false
+        })
+    }
+
+    #[wasm_bindgen_test]
+    fn hash_mode_round_trip_test() {
+        // ====== ARRANGE ======
+        let route = Route::Home(HomeQuery {
+            feed: None,
+            tag: Some(Tag::from("rust".to_owned())),
+            page: PageNumber::from(2),
+        });
+        let config = Config {
+            mode: Mode::Hash,
+            base_path: String::new(),
+        };
+
+        // ====== ACT ======
+        let parsed_route = parse(route.to_url(&config), &config);
+
+        // ====== ASSERT ======
+        assert_eq!(route.href(&config), "/#/?tag=rust&page=2");
+        assert!(if let Ok(Route::Home(query)) = parsed_route {
+            query.tag == Some(Tag::from("rust".to_owned())) && query.page == PageNumber::from(2)
+        } else {
+            false
+        })
+    }
+
+    #[wasm_bindgen_test]
+    fn base_path_round_trip_test() {
+        // ====== ARRANGE ======
+        let route = Route::EditArticle(Slug::from("my_article".to_owned()));
+        let config = Config {
+            mode: Mode::Path,
+            base_path: "/apps/conduit".into(),
+        };
+
+        // ====== ACT ======
+        let href = route.href(&config);
+        let url = seed::Url::try_from(href.clone()).expect("invalid href");
+        let parsed_route = parse(url, &config);
+
+        // ====== ASSERT ======
+        assert_eq!(href, "/apps/conduit/editor/my_article");
+        assert!(if let Ok(Route::EditArticle(slug)) = parsed_route {
+            slug.as_str() == "my_article"
+        } else {
+            false
+        })
+    }
+
+    #[wasm_bindgen_test]
+    fn base_path_home_round_trip_test() {
+        // ====== ARRANGE ======
+        let route = Route::Home(HomeQuery {
+            feed: Some(HomeFeed::Global),
+            tag: None,
+            page: PageNumber::default(),
+        });
+        let config = Config {
+            mode: Mode::Path,
+            base_path: "/apps/conduit".into(),
+        };
+
+        // ====== ACT ======
+        let url = route.to_url(&config);
+        let parsed_route = parse(url.clone(), &config);
+
+        // ====== ASSERT ======
+        assert_eq!(route.href(&config), "/apps/conduit/?feed=global");
+        assert_eq!(url.path, vec!["apps", "conduit", ""]);
+        assert!(if let Ok(Route::Home(query)) = parsed_route {
+            query.feed == Some(HomeFeed::Global)
+        } else {
+            false
+        })
+    }
+
+    #[wasm_bindgen_test]
+    fn base_path_hash_mode_round_trip_test() {
+        // ====== ARRANGE ======
+        let route = Route::Article(Slug::from("my_article".to_owned()));
+        let config = Config {
+            mode: Mode::Hash,
+            base_path: "/apps/conduit".into(),
+        };
+
+        // ====== ACT ======
+        let href = route.href(&config);
+        let url = seed::Url::try_from(href.clone()).expect("invalid href");
+        let parsed_route = parse(url, &config);
+
+        // ====== ASSERT ======
+        assert_eq!(href, "/apps/conduit/#/article/my_article");
+        assert!(if let Ok(Route::Article(slug)) = parsed_route {
+            slug.as_str() == "my_article"
+        } else {
+            false
+        })
+    }
+
+    #[wasm_bindgen_test]
+    fn outside_base_path_route_test() {
+        // ====== ARRANGE ======
+        let url = seed::Url::new(vec!["article", "my_article"]);
+        let config = Config {
+            mode: Mode::Path,
+            base_path: "/apps/conduit".into(),
+        };
+
+        // ====== ACT ======
+        let route = parse(url, &config);
+
+        // ====== ASSERT ======
+        assert!(route.is_err())
+    }
+
     #[wasm_bindgen_test]
     fn invalid_route_test() {
         // ====== ARRANGE ======
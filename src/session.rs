@@ -1,28 +1,131 @@
-use crate::entity::Viewer;
+use crate::{
+    api::{ConduitApi, HttpConduit},
+    entity::{Preferences, Username, Viewer},
+    storage,
+};
+use std::{fmt, rc::Rc};
 
-#[derive(Clone, Debug)]
-pub enum Session {
-    LoggedIn(Viewer),
-    Guest,
+#[derive(Clone)]
+pub struct Session {
+    viewer: Option<Viewer>,
+    /// All signed-in viewers, including the active one.
+    accounts: Vec<Viewer>,
+    /// Preferences of the viewer (or the guest).
+    preferences: Preferences,
+    api: Rc<dyn ConduitApi>,
 }
 
 impl<'a> Default for Session {
     fn default() -> Self {
-        Self::Guest
+        Self {
+            viewer: None,
+            accounts: Vec::new(),
+            preferences: Preferences::default(),
+            api: Rc::new(HttpConduit),
+        }
+    }
+}
+
+impl fmt::Debug for Session {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Session")
+            .field("viewer", &self.viewer)
+            .field("accounts", &self.accounts)
+            .field("preferences", &self.preferences)
+            .finish_non_exhaustive()
     }
 }
 
 impl<'a> Session {
-    pub fn new(viewer: Option<Viewer>) -> Self {
-        match viewer {
-            Some(viewer) => Self::LoggedIn(viewer),
-            None => Self::Guest,
+    pub fn new(viewer: Option<Viewer>, api: Rc<dyn ConduitApi>) -> Self {
+        Self {
+            accounts: viewer.iter().cloned().collect(),
+            preferences: load_preferences(viewer.as_ref()),
+            viewer,
+            api,
         }
     }
+    pub fn with_accounts(mut self, accounts: Vec<Viewer>) -> Self {
+        self.accounts = accounts;
+        self
+    }
+    pub fn with_preferences(mut self, preferences: Preferences) -> Self {
+        self.preferences = preferences;
+        self
+    }
     pub fn viewer(&self) -> Option<&Viewer> {
-        match self {
-            Self::LoggedIn(viewer) => Some(viewer),
-            Self::Guest => None,
+        self.viewer.as_ref()
+    }
+    pub fn accounts(&self) -> &[Viewer] {
+        &self.accounts
+    }
+    /// Signed-in viewers other than the active one.
+    pub fn other_accounts(&self) -> impl Iterator<Item = &Viewer> {
+        let active = self.viewer().map(Viewer::username);
+        self.accounts
+            .iter()
+            .filter(move |viewer| Some(viewer.username()) != active)
+    }
+    pub const fn preferences(&self) -> &Preferences {
+        &self.preferences
+    }
+    pub fn api(&self) -> &dyn ConduitApi {
+        self.api.as_ref()
+    }
+    /// The same backend with the given viewer.
+    /// The viewer is added to accounts (or replaces the one of the same account).
+    pub fn logged_in(&self, viewer: Viewer) -> Self {
+        let mut accounts = self.accounts.clone();
+        match accounts
+            .iter_mut()
+            .find(|account| account.is_same_account(&viewer))
+        {
+            Some(account) => *account = viewer.clone(),
+            None => accounts.push(viewer.clone()),
+        }
+        Self {
+            preferences: load_preferences(Some(&viewer)),
+            viewer: Some(viewer),
+            accounts,
+            api: Rc::clone(&self.api),
+        }
+    }
+    /// The same backend with viewers changed by another tab.
+    pub fn synced(&self, viewer: Option<Viewer>, accounts: Vec<Viewer>) -> Self {
+        Self {
+            preferences: load_preferences(viewer.as_ref()),
+            viewer,
+            accounts,
+            api: Rc::clone(&self.api),
+        }
+    }
+    /// The same backend without viewer - the viewer has been signed out.
+    pub fn guest(&self) -> Self {
+        match self.viewer() {
+            Some(viewer) => self.signed_out(viewer.username()),
+            None => self.clone(),
+        }
+    }
+    /// The same backend without the account with the given username.
+    pub fn signed_out(&self, username: &Username) -> Self {
+        let viewer = self
+            .viewer
+            .clone()
+            .filter(|viewer| viewer.username() != username);
+        Self {
+            preferences: load_preferences(viewer.as_ref()),
+            viewer,
+            accounts: self
+                .accounts
+                .iter()
+                .filter(|account| account.username() != username)
+                .cloned()
+                .collect(),
+            api: Rc::clone(&self.api),
         }
     }
 }
+
+fn load_preferences(viewer: Option<&Viewer>) -> Preferences {
+    storage::load_preferences(viewer.map(Viewer::username))
+}
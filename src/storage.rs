@@ -1,32 +1,494 @@
-use crate::entity::Viewer;
-use seed::storage;
-use serde_json;
+use crate::{
+    entity::{
+        ArticleDraft, CommentDraft, DraftTarget, PendingMutation, Preferences, Slug, Username,
+        Viewer,
+    },
+    logger,
+};
+use backend::Backend;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{self, Value};
+use std::{cell::RefCell, rc::Rc};
+
+pub mod backend;
+pub mod backup;
+pub mod schema;
 
 const STORAGE_KEY: &str = "conduit";
+const API_URL_STORAGE_KEY: &str = "conduit_api_url";
+const PENDING_MUTATIONS_STORAGE_KEY: &str = "conduit_pending_mutations";
+const ARTICLE_DRAFTS_STORAGE_KEY: &str = "conduit_article_drafts";
+const COMMENT_DRAFTS_STORAGE_KEY: &str = "conduit_comment_drafts";
+const PREFERENCES_STORAGE_KEY: &str = "conduit_preferences";
+
+thread_local! {
+    /// The API URL, remembered viewers and data made on their behalf, and guest data.
+    static PERSISTENT_BACKEND: RefCell<Rc<dyn Backend>> = RefCell::new(backend::local());
+    /// Viewers signed in to this tab without "Remember me" and data made on their behalf.
+    static SESSION_BACKEND: RefCell<Rc<dyn Backend>> = RefCell::new(backend::session());
+}
+
+/// Suggested "Remember me" - it's off when this tab has been signed in without it.
+pub fn remember_me() -> bool {
+    session_backend().get_item(STORAGE_KEY).is_none()
+}
+
+// ------ Accounts ------
+
+/// All signed-in viewers; pages work with the active one.
+#[derive(Default, Deserialize, Serialize)]
+struct Accounts {
+    active: Option<Username<'static>>,
+    viewers: Vec<Viewer>,
+    /// Viewers signed in without "Remember me" - they are stored in `SESSION_BACKEND`.
+    #[serde(skip)]
+    session_only: Vec<Username<'static>>,
+}
+
+impl Accounts {
+    fn is_session_only(&self, username: &Username) -> bool {
+        self.session_only
+            .iter()
+            .any(|session_only| session_only == username)
+    }
+
+    /// Add the viewer (or replace the one of the same account) and make it active.
+    fn upsert(&mut self, viewer: &Viewer) {
+        match self
+            .viewers
+            .iter_mut()
+            .find(|stored_viewer| stored_viewer.is_same_account(viewer))
+        {
+            Some(stored_viewer) => *stored_viewer = viewer.clone(),
+            None => self.viewers.push(viewer.clone()),
+        }
+        self.active = Some(viewer.username().to_static());
+    }
+
+    fn active_viewer(&self) -> Option<&Viewer> {
+        let active = self.active.as_ref()?;
+        self.viewers
+            .iter()
+            .find(|viewer| viewer.username() == active)
+    }
+}
+
+/// Whether the changed storage item (`None` means cleared storage) contains viewers.
+pub fn is_viewers_key(key: Option<&str>) -> bool {
+    key.is_none_or(|key| key == STORAGE_KEY)
+}
 
+/// The active viewer.
 pub fn load_viewer() -> Option<Viewer> {
-    local_storage()
-        .get_item(STORAGE_KEY)
-        .expect("try to get local storage item failed")
-        .map(|serialized_item| {
-            serde_json::from_str(&serialized_item).expect("viewer deserialization failed")
-        })
+    load_accounts().active_viewer().cloned()
 }
 
+/// All signed-in viewers.
+pub fn load_viewers() -> Vec<Viewer> {
+    load_accounts().viewers
+}
+
+/// Add the viewer (or replace the one of the same account) and make it active.
 pub fn store_viewer(viewer: &Viewer) {
-    storage::store_data(&local_storage(), STORAGE_KEY, viewer);
+    let mut accounts = load_accounts();
+    accounts.upsert(viewer);
+    store_accounts(&accounts);
+}
+
+/// Store the viewer who has just signed in - see `store_viewer`.
+///
+/// The viewer and their data are kept in the local storage with `remember_me`,
+/// otherwise only until the tab is closed. Other viewers keep their choice.
+pub fn store_signed_in_viewer(viewer: &Viewer, remember_me: bool) {
+    let username = viewer.username();
+    let mut accounts = load_accounts();
+    accounts
+        .session_only
+        .retain(|session_only| session_only != username);
+    if !remember_me {
+        accounts.session_only.push(username.to_static());
+    }
+    accounts.upsert(viewer);
+    store_accounts(&accounts);
+
+    let (from, to) = if remember_me {
+        (session_backend(), persistent_backend())
+    } else {
+        (persistent_backend(), session_backend())
+    };
+    move_viewer_data(username, from.as_ref(), to.as_ref());
+}
+
+/// Make the stored viewer active.
+pub fn activate_viewer(username: &Username) -> Option<Viewer> {
+    let mut accounts = load_accounts();
+    let viewer = accounts
+        .viewers
+        .iter()
+        .find(|viewer| viewer.username() == username)
+        .cloned()?;
+    accounts.active = Some(username.to_static());
+    store_accounts(&accounts);
+    Some(viewer)
 }
 
+/// Sign out the viewer - delete them and their pending mutations.
+///
+/// Drafts and preferences are kept - e.g. when the session has expired.
+/// They are used again when the viewer signs in.
+pub fn delete_viewer(username: &Username) {
+    let pending_mutations_key = viewer_storage_key(PENDING_MUTATIONS_STORAGE_KEY, Some(username));
+    let pending_mutations_backend = backend_for(&pending_mutations_key, Some(username));
+
+    let mut accounts = load_accounts();
+    accounts
+        .viewers
+        .retain(|viewer| viewer.username() != username);
+    accounts
+        .session_only
+        .retain(|session_only| session_only != username);
+    if accounts.active.as_ref() == Some(username) {
+        accounts.active = None;
+    }
+    store_accounts(&accounts);
+    pending_mutations_backend.remove_item(&pending_mutations_key);
+}
+
+pub fn delete_active_viewer() {
+    if let Some(username) = load_accounts().active {
+        delete_viewer(&username);
+    }
+}
+
+/// Delete all viewers and data made on their behalf (e.g. pending mutations or drafts).
 pub fn delete_app_data() {
-    local_storage()
-        .remove_item(STORAGE_KEY)
-        .expect("remove item from local storage failed");
+    for backend in &backends() {
+        for key in viewer_data_keys(backend.as_ref()) {
+            backend.remove_item(&key);
+        }
+    }
+}
+
+/// Sign out all viewers and delete their pending mutations - e.g. when the backend has changed.
+///
+/// Drafts and preferences are kept.
+pub fn delete_sessions() {
+    for backend in &backends() {
+        for key in backend.keys() {
+            if key == STORAGE_KEY || key.starts_with(PENDING_MUTATIONS_STORAGE_KEY) {
+                backend.remove_item(&key);
+            }
+        }
+    }
+}
+
+pub fn load_api_url() -> Option<String> {
+    persistent_backend()
+        .get_item(API_URL_STORAGE_KEY)
+        .and_then(|serialized_item| serde_json::from_str(&serialized_item).ok())
+}
+
+pub fn store_api_url(api_url: &str) {
+    store_data(persistent_backend().as_ref(), API_URL_STORAGE_KEY, &api_url);
+}
+
+pub fn delete_api_url() {
+    persistent_backend().remove_item(API_URL_STORAGE_KEY);
+}
+
+/// Pending mutations of the viewer or of the guest (`None`).
+pub fn load_pending_mutations(username: Option<&Username>) -> Vec<PendingMutation> {
+    load_viewer_data(PENDING_MUTATIONS_STORAGE_KEY, username)
+}
+
+pub fn store_pending_mutations(username: Option<&Username>, pending_mutations: &[PendingMutation]) {
+    store_viewer_data(PENDING_MUTATIONS_STORAGE_KEY, username, &pending_mutations);
+}
+
+/// Article drafts of the viewer, the most recently edited first.
+///
+/// The viewer doesn't have to be signed in - drafts outlive expired sessions.
+pub fn load_article_drafts(username: &Username) -> Vec<ArticleDraft> {
+    let mut drafts: Vec<ArticleDraft> =
+        load_viewer_data(ARTICLE_DRAFTS_STORAGE_KEY, Some(username));
+    drafts.sort_by(|a, b| b.edited_at_ms.total_cmp(&a.edited_at_ms));
+    drafts
+}
+
+pub fn load_article_draft(username: &Username, target: &DraftTarget) -> Option<ArticleDraft> {
+    load_article_drafts(username)
+        .into_iter()
+        .find(|draft| &draft.target == target)
+}
+
+/// Insert the draft or replace the one with the same target.
+pub fn store_article_draft(username: &Username, draft: &ArticleDraft) {
+    let mut drafts = load_article_drafts(username);
+    drafts.retain(|stored_draft| stored_draft.target != draft.target);
+    drafts.push(draft.clone());
+    store_article_drafts(username, &drafts);
+}
+
+pub fn delete_article_draft(username: &Username, target: &DraftTarget) {
+    let mut drafts = load_article_drafts(username);
+    drafts.retain(|draft| &draft.target != target);
+    store_article_drafts(username, &drafts);
+}
+
+/// Comment draft of the viewer (`None` for guests) for the article.
+///
+/// Drafts older than `max_age_ms` are deleted.
+pub fn load_comment_draft(
+    username: Option<&Username>,
+    slug: &Slug,
+    max_age_ms: f64,
+    now_ms: f64,
+) -> Option<CommentDraft> {
+    let drafts = load_comment_drafts(username);
+    let draft_count = drafts.len();
+    let drafts = drafts
+        .into_iter()
+        .filter(|draft| !draft.is_expired(max_age_ms, now_ms))
+        .collect::<Vec<_>>();
+    if drafts.len() != draft_count {
+        store_comment_drafts(username, &drafts);
+    }
+    drafts.into_iter().find(|draft| draft.slug == slug.as_str())
+}
+
+/// Insert the draft or replace the one for the same article.
+pub fn store_comment_draft(username: Option<&Username>, draft: &CommentDraft) {
+    let mut drafts = load_comment_drafts(username);
+    drafts.retain(|stored_draft| stored_draft.slug != draft.slug);
+    drafts.push(draft.clone());
+    store_comment_drafts(username, &drafts);
+}
+
+pub fn delete_comment_draft(username: Option<&Username>, slug: &Slug) {
+    let mut drafts = load_comment_drafts(username);
+    drafts.retain(|draft| draft.slug != slug.as_str());
+    store_comment_drafts(username, &drafts);
+}
+
+/// Preferences of the viewer with the given username (`None` for guests).
+///
+/// Default preferences are returned when there are no (readable) stored ones.
+pub fn load_preferences(username: Option<&Username>) -> Preferences {
+    load_viewer_data(PREFERENCES_STORAGE_KEY, username)
+}
+
+pub fn store_preferences(username: Option<&Username>, preferences: &Preferences) {
+    store_viewer_data(PREFERENCES_STORAGE_KEY, username, preferences);
 }
 
 // ====== PRIVATE ======
 
-fn local_storage() -> storage::Storage {
-    storage::get_storage().expect("get local storage failed")
+/// Remembered viewers together with the ones signed in to this tab without "Remember me".
+///
+/// The tab has its own active viewer once it has been signed in without "Remember me".
+fn load_accounts() -> Accounts {
+    let mut accounts = load_accounts_from(persistent_backend().as_ref());
+    let session_accounts = load_accounts_from(session_backend().as_ref());
+    if session_accounts.viewers.is_empty() {
+        return accounts;
+    }
+    accounts.active = session_accounts.active;
+    for viewer in session_accounts.viewers {
+        accounts
+            .viewers
+            .retain(|stored_viewer| stored_viewer.username() != viewer.username());
+        accounts.session_only.push(viewer.username().to_static());
+        accounts.viewers.push(viewer);
+    }
+    accounts
+}
+
+fn store_accounts(accounts: &Accounts) {
+    let (session_viewers, remembered_viewers): (Vec<_>, Vec<_>) = accounts
+        .viewers
+        .iter()
+        .cloned()
+        .partition(|viewer| accounts.is_session_only(viewer.username()));
+    if session_viewers.is_empty() {
+        session_backend().remove_item(STORAGE_KEY);
+        store_accounts_to(
+            persistent_backend().as_ref(),
+            &Accounts {
+                active: accounts.active.clone(),
+                viewers: remembered_viewers,
+                session_only: Vec::new(),
+            },
+        );
+        return;
+    }
+
+    // Other tabs keep their active viewer.
+    let remembered_active = load_accounts_from(persistent_backend().as_ref())
+        .active
+        .filter(|active| {
+            remembered_viewers
+                .iter()
+                .any(|viewer| viewer.username() == active)
+        });
+    store_accounts_to(
+        persistent_backend().as_ref(),
+        &Accounts {
+            active: remembered_active,
+            viewers: remembered_viewers,
+            session_only: Vec::new(),
+        },
+    );
+    store_accounts_to(
+        session_backend().as_ref(),
+        &Accounts {
+            active: accounts.active.clone(),
+            viewers: session_viewers,
+            session_only: Vec::new(),
+        },
+    );
+}
+
+/// Unreadable data (e.g. stored by a newer version of the app) are deleted.
+fn load_accounts_from(backend: &dyn Backend) -> Accounts {
+    let serialized_item = match backend.get_item(STORAGE_KEY) {
+        Some(serialized_item) => serialized_item,
+        None => return Accounts::default(),
+    };
+
+    let upgraded = schema::upgrade(&serialized_item).and_then(|upgraded| {
+        let from_version = upgraded.from_version;
+        serde_json::from_value::<Accounts>(upgraded.data)
+            .map(|accounts| (from_version, accounts))
+            .map_err(schema::Error::from)
+    });
+    match upgraded {
+        Ok((from_version, accounts)) => {
+            if from_version != schema::CURRENT_VERSION {
+                store_accounts_to(backend, &accounts);
+            }
+            accounts
+        }
+        Err(error) => {
+            logger::warning(format!("Discarding stored viewers: {}", error));
+            backend.remove_item(STORAGE_KEY);
+            Accounts::default()
+        }
+    }
+}
+
+fn store_accounts_to(backend: &dyn Backend, accounts: &Accounts) {
+    store_data(backend, STORAGE_KEY, &accounts_to_value(accounts));
+}
+
+/// Viewers in the versioned envelope.
+fn accounts_to_value(accounts: &Accounts) -> Value {
+    schema::wrap(serde_json::to_value(accounts).expect("viewers serialization failed"))
+}
+
+fn store_article_drafts(username: &Username, drafts: &[ArticleDraft]) {
+    store_viewer_data(ARTICLE_DRAFTS_STORAGE_KEY, Some(username), &drafts);
+}
+
+fn load_comment_drafts(username: Option<&Username>) -> Vec<CommentDraft> {
+    load_viewer_data(COMMENT_DRAFTS_STORAGE_KEY, username)
+}
+
+fn store_comment_drafts(username: Option<&Username>, drafts: &[CommentDraft]) {
+    store_viewer_data(COMMENT_DRAFTS_STORAGE_KEY, username, &drafts);
+}
+
+/// Missing or unreadable data are replaced with the default value.
+fn load_viewer_data<T: DeserializeOwned + Default>(
+    base_key: &str,
+    username: Option<&Username>,
+) -> T {
+    let key = viewer_storage_key(base_key, username);
+    backend_for(&key, username)
+        .get_item(&key)
+        .and_then(|serialized_item| serde_json::from_str(&serialized_item).ok())
+        .unwrap_or_default()
+}
+
+fn store_viewer_data(base_key: &str, username: Option<&Username>, data: &impl Serialize) {
+    let key = viewer_storage_key(base_key, username);
+    store_data(backend_for(&key, username).as_ref(), &key, data);
+}
+
+/// Data of viewers signed in without "Remember me" are kept in the session storage
+/// - also after their session has expired. Everything else is in the local storage.
+fn backend_for(key: &str, username: Option<&Username>) -> Rc<dyn Backend> {
+    let session_backend = session_backend();
+    let is_session_only = username.is_some_and(|username| {
+        load_accounts_from(session_backend.as_ref())
+            .viewers
+            .iter()
+            .any(|viewer| viewer.username() == username)
+    });
+    if is_session_only || session_backend.get_item(key).is_some() {
+        session_backend
+    } else {
+        persistent_backend()
+    }
+}
+
+/// Each viewer has their own data (e.g. pending mutations); guests use the key without a suffix.
+fn viewer_storage_key(base_key: &str, username: Option<&Username>) -> String {
+    match username {
+        Some(username) => format!("{}_{}", base_key, username.as_str()),
+        None => base_key.to_owned(),
+    }
+}
+
+/// Viewers and data made on their behalf.
+fn is_viewer_data_key(key: &str) -> bool {
+    key == STORAGE_KEY
+        || key.starts_with(PENDING_MUTATIONS_STORAGE_KEY)
+        || key.starts_with(ARTICLE_DRAFTS_STORAGE_KEY)
+        || key.starts_with(COMMENT_DRAFTS_STORAGE_KEY)
+        || key.starts_with(PREFERENCES_STORAGE_KEY)
+}
+
+fn viewer_data_keys(backend: &dyn Backend) -> Vec<String> {
+    backend
+        .keys()
+        .into_iter()
+        .filter(|key| is_viewer_data_key(key))
+        .collect()
+}
+
+/// Move data made on behalf of the viewer - e.g. when they sign in with another "Remember me".
+fn move_viewer_data(username: &Username, from: &dyn Backend, to: &dyn Backend) {
+    for base_key in &[
+        PENDING_MUTATIONS_STORAGE_KEY,
+        ARTICLE_DRAFTS_STORAGE_KEY,
+        COMMENT_DRAFTS_STORAGE_KEY,
+        PREFERENCES_STORAGE_KEY,
+    ] {
+        let key = viewer_storage_key(base_key, Some(username));
+        if let Some(item) = from.get_item(&key) {
+            to.set_item(&key, &item);
+            from.remove_item(&key);
+        }
+    }
+}
+
+fn store_data(backend: &dyn Backend, key: &str, data: &impl Serialize) {
+    backend.set_item(
+        key,
+        &serde_json::to_string(data).expect("data serialization failed"),
+    );
+}
+
+fn persistent_backend() -> Rc<dyn Backend> {
+    PERSISTENT_BACKEND.with(|backend| Rc::clone(&backend.borrow()))
+}
+
+fn session_backend() -> Rc<dyn Backend> {
+    SESSION_BACKEND.with(|backend| Rc::clone(&backend.borrow()))
+}
+
+fn backends() -> [Rc<dyn Backend>; 2] {
+    [persistent_backend(), session_backend()]
 }
 
 // ====== ====== TESTS ====== ======
@@ -34,19 +496,23 @@ fn local_storage() -> storage::Storage {
 #[cfg(test)]
 pub mod tests {
     use super::*;
-    use crate::entity::{Avatar, Profile, Username};
+    use crate::entity::{preferences::Theme, Avatar, Profile, Username};
     use wasm_bindgen_test::*;
 
     wasm_bindgen_test_configure!(run_in_browser);
 
-    fn clean_local_storage() {
-        local_storage().clear().expect("clear storage failed");
+    /// Replace browser storages with new in-memory backends.
+    pub(crate) fn use_in_memory_backend() {
+        PERSISTENT_BACKEND
+            .with(|backend| *backend.borrow_mut() = Rc::new(backend::InMemory::default()));
+        SESSION_BACKEND
+            .with(|backend| *backend.borrow_mut() = Rc::new(backend::InMemory::default()));
     }
 
     #[wasm_bindgen_test]
     fn load_viewer_none_test() {
         // ====== ARRANGE ======
-        clean_local_storage();
+        use_in_memory_backend();
 
         // ====== ACT & ASSERT ======
         assert!(load_viewer().is_none())
@@ -55,7 +521,7 @@ pub mod tests {
     #[wasm_bindgen_test]
     fn store_view_test() {
         // ====== ARRANGE ======
-        clean_local_storage();
+        use_in_memory_backend();
 
         let viewer = Viewer {
             profile: Profile {
@@ -73,10 +539,121 @@ pub mod tests {
         assert!(load_viewer().is_some());
     }
 
+    #[wasm_bindgen_test]
+    fn load_viewer_migrates_unversioned_viewer_test() {
+        // ====== ARRANGE ======
+        use_in_memory_backend();
+        persistent_backend().set_item(
+            STORAGE_KEY,
+            r#"{"profile":{"bio":null,"avatar":null,"username":"John"},"auth_token":"token"}"#,
+        );
+
+        // ====== ACT ======
+        let viewer = load_viewer();
+
+        // ====== ASSERT ======
+        assert_eq!(
+            viewer.map(|viewer| viewer.auth_token).as_deref(),
+            Some("token")
+        );
+        let stored = persistent_backend()
+            .get_item(STORAGE_KEY)
+            .expect("viewer has been deleted");
+        assert_eq!(
+            schema::upgrade(&stored)
+                .map(|upgraded| upgraded.from_version)
+                .ok(),
+            Some(schema::CURRENT_VERSION)
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn load_viewer_discards_unreadable_data_test() {
+        // ====== ARRANGE ======
+        use_in_memory_backend();
+        persistent_backend().set_item(STORAGE_KEY, r#"{"version":1,"data":{"auth_token":42}}"#);
+
+        // ====== ACT ======
+        let viewer = load_viewer();
+
+        // ====== ASSERT ======
+        assert!(viewer.is_none());
+        assert!(persistent_backend().get_item(STORAGE_KEY).is_none());
+    }
+
+    pub(super) fn viewer(username: &str) -> Viewer {
+        Viewer {
+            profile: Profile {
+                username: username.to_owned().into(),
+                avatar: Avatar::new(None as Option<&str>),
+                bio: None,
+            },
+            auth_token: format!("{}-token", username),
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn store_viewer_adds_account_test() {
+        // ====== ARRANGE ======
+        use_in_memory_backend();
+        store_viewer(&viewer("personal"));
+
+        // ====== ACT ======
+        store_viewer(&viewer("team"));
+
+        // ====== ASSERT ======
+        assert_eq!(load_viewers().len(), 2);
+        assert_eq!(
+            load_viewer().map(|viewer| viewer.auth_token).as_deref(),
+            Some("team-token")
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn activate_viewer_test() {
+        // ====== ARRANGE ======
+        use_in_memory_backend();
+        store_viewer(&viewer("personal"));
+        store_viewer(&viewer("team"));
+
+        // ====== ACT ======
+        let activated = activate_viewer(&"personal".into());
+
+        // ====== ASSERT ======
+        assert!(activated.is_some());
+        assert_eq!(
+            load_viewer().map(|viewer| viewer.auth_token).as_deref(),
+            Some("personal-token")
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn delete_viewer_keeps_other_accounts_test() {
+        // ====== ARRANGE ======
+        use_in_memory_backend();
+        store_viewer(&viewer("personal"));
+        store_viewer(&viewer("team"));
+        store_pending_mutations(
+            Some(&Username::from("team")),
+            &[PendingMutation::Follow {
+                username: "John".into(),
+            }],
+        );
+
+        // ====== ACT ======
+        delete_active_viewer();
+
+        // ====== ASSERT ======
+        assert!(load_viewer().is_none());
+        assert_eq!(load_viewers().len(), 1);
+        store_viewer(&viewer("team"));
+        assert!(load_pending_mutations(Some(&Username::from("team"))).is_empty());
+    }
+
     #[wasm_bindgen_test]
     fn delete_app_data_test() {
         // ====== ARRANGE ======
-        clean_local_storage();
+        use_in_memory_backend();
 
         let viewer = Viewer {
             profile: Profile {
@@ -94,4 +671,281 @@ pub mod tests {
         // ====== ASSERT ======
         assert!(load_viewer().is_none());
     }
+
+    #[wasm_bindgen_test]
+    fn delete_sessions_keeps_drafts_and_preferences_test() {
+        // ====== ARRANGE ======
+        use_in_memory_backend();
+        let personal = viewer("personal");
+        store_viewer(&personal);
+        store_pending_mutations(
+            Some(personal.username()),
+            &[PendingMutation::Follow {
+                username: "John".into(),
+            }],
+        );
+        store_article_draft(
+            personal.username(),
+            &article_draft(DraftTarget::New { id: 1 }, 1.),
+        );
+        let preferences = Preferences {
+            theme: Theme::Dark,
+            ..Preferences::default()
+        };
+        store_preferences(Some(personal.username()), &preferences);
+
+        // ====== ACT ======
+        delete_sessions();
+
+        // ====== ASSERT ======
+        assert!(load_viewers().is_empty());
+        assert!(load_pending_mutations(Some(personal.username())).is_empty());
+        assert_eq!(load_article_drafts(personal.username()).len(), 1);
+        assert_eq!(load_preferences(Some(personal.username())), preferences);
+    }
+
+    #[wasm_bindgen_test]
+    fn delete_app_data_deletes_pending_mutations_test() {
+        // ====== ARRANGE ======
+        use_in_memory_backend();
+        store_viewer(&viewer("personal"));
+        store_pending_mutations(
+            Some(&Username::from("personal")),
+            &[PendingMutation::Follow {
+                username: "John".into(),
+            }],
+        );
+
+        // ====== ACT ======
+        delete_app_data();
+
+        // ====== ASSERT ======
+        assert!(load_pending_mutations(Some(&Username::from("personal"))).is_empty());
+    }
+
+    fn article_draft(target: DraftTarget, edited_at_ms: f64) -> ArticleDraft {
+        ArticleDraft {
+            target,
+            title: "Title".into(),
+            description: String::new(),
+            body: "Body".into(),
+            tags: String::new(),
+            edited_at_ms,
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn store_article_draft_replaces_draft_with_same_target_test() {
+        // ====== ARRANGE ======
+        use_in_memory_backend();
+        let personal = viewer("personal");
+        let username = personal.username();
+        store_article_draft(username, &article_draft(DraftTarget::New { id: 1 }, 1.));
+        store_article_draft(
+            username,
+            &article_draft(
+                DraftTarget::Edit {
+                    slug: "slug".into(),
+                },
+                2.,
+            ),
+        );
+
+        // ====== ACT ======
+        store_article_draft(username, &article_draft(DraftTarget::New { id: 1 }, 3.));
+
+        // ====== ASSERT ======
+        let edited_at = load_article_drafts(username)
+            .iter()
+            .map(|draft| draft.edited_at_ms)
+            .collect::<Vec<_>>();
+        assert_eq!(edited_at, vec![3., 2.]);
+    }
+
+    #[wasm_bindgen_test]
+    fn delete_active_viewer_keeps_drafts_test() {
+        // ====== ARRANGE ======
+        use_in_memory_backend();
+        let personal = viewer("personal");
+        store_viewer(&personal);
+        store_article_draft(
+            personal.username(),
+            &article_draft(DraftTarget::New { id: 1 }, 1.),
+        );
+        store_comment_draft(
+            Some(personal.username()),
+            &CommentDraft::new(&Slug::from("slug".to_owned()), "Comment", 1.),
+        );
+
+        // ====== ACT ======
+        // The session has expired or a request has returned 401.
+        delete_active_viewer();
+        store_viewer(&personal);
+
+        // ====== ASSERT ======
+        assert_eq!(load_article_drafts(personal.username()).len(), 1);
+        assert_eq!(load_comment_drafts(Some(personal.username())).len(), 1);
+    }
+
+    #[wasm_bindgen_test]
+    fn load_comment_draft_deletes_expired_drafts_test() {
+        // ====== ARRANGE ======
+        use_in_memory_backend();
+        let personal = viewer("personal");
+        let username = Some(personal.username());
+        let slug = Slug::from("fresh".to_owned());
+        store_comment_draft(username, &CommentDraft::new(&slug, "Fresh", 900.));
+        store_comment_draft(
+            username,
+            &CommentDraft::new(&Slug::from("stale".to_owned()), "Stale", 100.),
+        );
+
+        // ====== ACT ======
+        let draft = load_comment_draft(username, &slug, 500., 1000.);
+
+        // ====== ASSERT ======
+        assert_eq!(draft.map(|draft| draft.body).as_deref(), Some("Fresh"));
+        assert_eq!(load_comment_drafts(username).len(), 1);
+    }
+
+    #[wasm_bindgen_test]
+    fn preferences_are_stored_per_viewer_test() {
+        // ====== ARRANGE ======
+        use_in_memory_backend();
+        let personal = viewer("personal");
+        let preferences = Preferences {
+            theme: Theme::Dark,
+            ..Preferences::default()
+        };
+
+        // ====== ACT ======
+        store_preferences(Some(personal.username()), &preferences);
+
+        // ====== ASSERT ======
+        assert_eq!(load_preferences(Some(personal.username())), preferences);
+        assert_eq!(load_preferences(None), Preferences::default());
+    }
+
+    #[wasm_bindgen_test]
+    fn delete_active_viewer_keeps_preferences_test() {
+        // ====== ARRANGE ======
+        use_in_memory_backend();
+        let personal = viewer("personal");
+        store_viewer(&personal);
+        let preferences = Preferences {
+            theme: Theme::Dark,
+            ..Preferences::default()
+        };
+        store_preferences(Some(personal.username()), &preferences);
+
+        // ====== ACT ======
+        delete_active_viewer();
+
+        // ====== ASSERT ======
+        assert_eq!(load_preferences(Some(personal.username())), preferences);
+    }
+
+    fn follow_john() -> Vec<PendingMutation> {
+        vec![PendingMutation::Follow {
+            username: "John".into(),
+        }]
+    }
+
+    #[wasm_bindgen_test]
+    fn store_signed_in_viewer_keeps_other_accounts_remembered_test() {
+        // ====== ARRANGE ======
+        use_in_memory_backend();
+        let personal = viewer("personal");
+        let work = viewer("work");
+        store_signed_in_viewer(&personal, true);
+        store_pending_mutations(Some(personal.username()), &follow_john());
+        store_preferences(Some(work.username()), &Preferences::default());
+        store_api_url("http://localhost:3000/api");
+
+        // ====== ACT ======
+        store_signed_in_viewer(&work, false);
+        store_pending_mutations(Some(work.username()), &follow_john());
+
+        // ====== ASSERT ======
+        assert!(!remember_me());
+        assert_eq!(
+            load_viewer().as_ref().map(Viewer::username),
+            Some(work.username())
+        );
+        assert_eq!(load_viewers().len(), 2);
+        assert_eq!(load_pending_mutations(Some(personal.username())).len(), 1);
+        assert_eq!(load_pending_mutations(Some(work.username())).len(), 1);
+
+        let mut persistent_keys = persistent_backend().keys();
+        persistent_keys.sort();
+        assert_eq!(
+            persistent_keys,
+            vec![
+                STORAGE_KEY.to_owned(),
+                API_URL_STORAGE_KEY.to_owned(),
+                viewer_storage_key(PENDING_MUTATIONS_STORAGE_KEY, Some(personal.username())),
+            ]
+        );
+        let mut session_keys = session_backend().keys();
+        session_keys.sort();
+        assert_eq!(
+            session_keys,
+            vec![
+                STORAGE_KEY.to_owned(),
+                viewer_storage_key(PENDING_MUTATIONS_STORAGE_KEY, Some(work.username())),
+                viewer_storage_key(PREFERENCES_STORAGE_KEY, Some(work.username())),
+            ]
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn store_signed_in_viewer_with_remember_me_test() {
+        // ====== ARRANGE ======
+        use_in_memory_backend();
+        let work = viewer("work");
+        store_signed_in_viewer(&work, false);
+        store_pending_mutations(Some(work.username()), &follow_john());
+
+        // ====== ACT ======
+        store_signed_in_viewer(&work, true);
+
+        // ====== ASSERT ======
+        assert!(remember_me());
+        assert!(session_backend().keys().is_empty());
+        assert_eq!(load_viewers().len(), 1);
+        assert_eq!(load_pending_mutations(Some(work.username())).len(), 1);
+    }
+
+    #[wasm_bindgen_test]
+    fn is_viewers_key_test() {
+        // ====== ACT & ASSERT ======
+        assert!(is_viewers_key(Some(STORAGE_KEY)));
+        assert!(is_viewers_key(None));
+        assert!(!is_viewers_key(Some(API_URL_STORAGE_KEY)));
+    }
+
+    #[wasm_bindgen_test]
+    fn store_api_url_test() {
+        // ====== ARRANGE ======
+        use_in_memory_backend();
+
+        // ====== ACT ======
+        store_api_url("http://localhost:3000/api");
+
+        // ====== ASSERT ======
+        assert_eq!(load_api_url().as_deref(), Some("http://localhost:3000/api"));
+    }
+
+    #[wasm_bindgen_test]
+    fn delete_app_data_keeps_api_url_test() {
+        // ====== ARRANGE ======
+        use_in_memory_backend();
+        store_api_url("http://localhost:3000/api");
+
+        // ====== ACT ======
+        delete_app_data();
+
+        // ====== ASSERT ======
+        assert!(load_api_url().is_some());
+    }
 }
@@ -0,0 +1,122 @@
+use crate::logger;
+use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+// ------ Backend ------
+
+/// Key-value store for serialized app data.
+pub trait Backend {
+    fn get_item(&self, key: &str) -> Option<String>;
+    fn set_item(&self, key: &str, value: &str);
+    fn remove_item(&self, key: &str);
+    fn keys(&self) -> Vec<String>;
+}
+
+/// The local storage, or `InMemory` when the browser doesn't allow storing data
+/// (e.g. blocked cookies).
+pub fn local() -> Rc<dyn Backend> {
+    match seed::window().local_storage().ok().flatten() {
+        Some(storage) => Rc::new(WebStorage::new(storage)),
+        None => {
+            logger::warning("Local storage is unavailable - data will be lost on page reload.");
+            Rc::new(InMemory::default())
+        }
+    }
+}
+
+/// The session storage (data are deleted when the browser tab is closed),
+/// or `InMemory` when the browser doesn't allow storing data.
+pub fn session() -> Rc<dyn Backend> {
+    match seed::window().session_storage().ok().flatten() {
+        Some(storage) => Rc::new(WebStorage::new(storage)),
+        None => {
+            logger::warning("Session storage is unavailable - data will be lost on page reload.");
+            Rc::new(InMemory::default())
+        }
+    }
+}
+
+// ------ WebStorage ------
+
+/// The browser's local or session storage.
+pub struct WebStorage {
+    storage: web_sys::Storage,
+}
+
+impl WebStorage {
+    pub const fn new(storage: web_sys::Storage) -> Self {
+        Self { storage }
+    }
+}
+
+impl Backend for WebStorage {
+    fn get_item(&self, key: &str) -> Option<String> {
+        self.storage.get_item(key).ok().flatten()
+    }
+    /// Failures (e.g. exceeded quota) are only logged.
+    fn set_item(&self, key: &str, value: &str) {
+        if let Err(error) = self.storage.set_item(key, value) {
+            logger::error(format!("set storage item `{}` failed: {:?}", key, error));
+        }
+    }
+    fn remove_item(&self, key: &str) {
+        if let Err(error) = self.storage.remove_item(key) {
+            logger::error(format!("remove storage item `{}` failed: {:?}", key, error));
+        }
+    }
+    fn keys(&self) -> Vec<String> {
+        (0..self.storage.length().unwrap_or_default())
+            .filter_map(|index| self.storage.key(index).ok().flatten())
+            .collect()
+    }
+}
+
+// ------ InMemory ------
+
+/// Data are deleted on page reload; used when browser storages are unavailable and in tests.
+#[derive(Default)]
+pub struct InMemory {
+    items: RefCell<BTreeMap<String, String>>,
+}
+
+impl Backend for InMemory {
+    fn get_item(&self, key: &str) -> Option<String> {
+        self.items.borrow().get(key).cloned()
+    }
+    fn set_item(&self, key: &str, value: &str) {
+        self.items
+            .borrow_mut()
+            .insert(key.to_owned(), value.to_owned());
+    }
+    fn remove_item(&self, key: &str) {
+        self.items.borrow_mut().remove(key);
+    }
+    fn keys(&self) -> Vec<String> {
+        self.items.borrow().keys().cloned().collect()
+    }
+}
+
+// ====== ====== TESTS ====== ======
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn in_memory_test() {
+        // ====== ARRANGE ======
+        let backend = InMemory::default();
+
+        // ====== ACT ======
+        backend.set_item("a", "1");
+        backend.set_item("b", "2");
+        backend.remove_item("a");
+
+        // ====== ASSERT ======
+        assert_eq!(backend.get_item("a"), None);
+        assert_eq!(backend.get_item("b").as_deref(), Some("2"));
+        assert_eq!(backend.keys(), vec!["b"]);
+    }
+}
@@ -0,0 +1,325 @@
+use super::{
+    accounts_to_value, backends, delete_api_url, delete_app_data, is_viewer_data_key,
+    load_accounts, load_api_url, persistent_backend, schema, store_accounts, store_api_url,
+    viewer_data_keys, Accounts, ARTICLE_DRAFTS_STORAGE_KEY, COMMENT_DRAFTS_STORAGE_KEY,
+    PENDING_MUTATIONS_STORAGE_KEY, PREFERENCES_STORAGE_KEY, STORAGE_KEY,
+};
+use crate::entity::{ArticleDraft, CommentDraft, PendingMutation, Preferences};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use std::{collections::BTreeMap, fmt};
+
+const FORMAT: &str = "conduit-backup";
+
+/// Increase it when the backup structure changes and handle older versions in `parse`.
+pub const CURRENT_VERSION: u32 = 1;
+
+// ------ Backup ------
+
+/// All data managed by `storage`.
+#[derive(Deserialize, Serialize)]
+struct Backup {
+    format: String,
+    version: u32,
+    /// `None` - the default API URL is used.
+    api_url: Option<String>,
+    /// Viewers and data made on their behalf by storage keys.
+    items: BTreeMap<String, Value>,
+}
+
+// ------ ImportMode ------
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ImportMode {
+    /// Add imported viewers and items which aren't stored yet; stored data win.
+    Merge,
+    /// Delete all stored data first.
+    Replace,
+}
+
+// ------ Error ------
+
+#[derive(Debug)]
+pub enum Error {
+    Json(serde_json::Error),
+    /// The file isn't a backup of this app.
+    UnsupportedFormat,
+    /// The backup has been made by a newer version of the app.
+    UnsupportedVersion(u32),
+    InvalidItem {
+        key: String,
+        message: String,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Json(error) => write!(f, "invalid JSON: {}", error),
+            Self::UnsupportedFormat => write!(f, "the file isn't a Conduit backup"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported backup version {}", version)
+            }
+            Self::InvalidItem { key, message } => write!(f, "invalid item {}: {}", key, message),
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Json(error)
+    }
+}
+
+/// Serialize all data managed by `storage` into a JSON document.
+pub fn export() -> String {
+    let mut items = BTreeMap::new();
+    for backend in &backends() {
+        for key in viewer_data_keys(backend.as_ref()) {
+            if key == STORAGE_KEY {
+                continue;
+            }
+            if let Some(value) = backend
+                .get_item(&key)
+                .and_then(|serialized_item| serde_json::from_str(&serialized_item).ok())
+            {
+                items.insert(key, value);
+            }
+        }
+    }
+    // Viewers remembered and signed in to this tab are exported together.
+    let accounts = load_accounts();
+    if !accounts.viewers.is_empty() {
+        items.insert(STORAGE_KEY.to_owned(), accounts_to_value(&accounts));
+    }
+    let backup = Backup {
+        format: FORMAT.to_owned(),
+        version: CURRENT_VERSION,
+        api_url: load_api_url(),
+        items,
+    };
+    serde_json::to_string_pretty(&backup).expect("backup serialization failed")
+}
+
+/// Validate the exported document and store its data.
+///
+/// Imported viewers are remembered. Nothing is changed when the document is invalid.
+pub fn import(serialized_backup: &str, mode: ImportMode) -> Result<(), Error> {
+    let Backup {
+        api_url, mut items, ..
+    } = parse(serialized_backup)?;
+    let imported_accounts = items
+        .remove(STORAGE_KEY)
+        .map(accounts_from_value)
+        .transpose()?;
+
+    let persistent_backend = persistent_backend();
+    match mode {
+        ImportMode::Replace => {
+            delete_app_data();
+            match api_url {
+                Some(api_url) => store_api_url(&api_url),
+                None => delete_api_url(),
+            }
+            for (key, value) in items {
+                persistent_backend.set_item(&key, &value.to_string());
+            }
+            if let Some(accounts) = imported_accounts {
+                store_accounts(&accounts);
+            }
+        }
+        ImportMode::Merge => {
+            if let (Some(api_url), None) = (api_url, load_api_url()) {
+                store_api_url(&api_url);
+            }
+            for (key, value) in items {
+                if backends()
+                    .iter()
+                    .all(|backend| backend.get_item(&key).is_none())
+                {
+                    persistent_backend.set_item(&key, &value.to_string());
+                }
+            }
+            if let Some(imported_accounts) = imported_accounts {
+                let mut accounts = load_accounts();
+                for viewer in imported_accounts.viewers {
+                    if !accounts
+                        .viewers
+                        .iter()
+                        .any(|stored_viewer| stored_viewer.is_same_account(&viewer))
+                    {
+                        accounts.viewers.push(viewer);
+                    }
+                }
+                if accounts.active.is_none() {
+                    accounts.active = imported_accounts.active;
+                }
+                store_accounts(&accounts);
+            }
+        }
+    }
+    Ok(())
+}
+
+// ====== PRIVATE ======
+
+fn parse(serialized_backup: &str) -> Result<Backup, Error> {
+    let value: Value = serde_json::from_str(serialized_backup)?;
+    if value.get("format").and_then(Value::as_str) != Some(FORMAT) {
+        return Err(Error::UnsupportedFormat);
+    }
+    let backup: Backup = serde_json::from_value(value)?;
+    if backup.version > CURRENT_VERSION {
+        return Err(Error::UnsupportedVersion(backup.version));
+    }
+    for (key, value) in &backup.items {
+        validate_item(key, value)?;
+    }
+    Ok(backup)
+}
+
+/// Items have to be readable by the current version of the app.
+fn validate_item(key: &str, value: &Value) -> Result<(), Error> {
+    let result = if !is_viewer_data_key(key) {
+        Err("unknown key".to_owned())
+    } else if key == STORAGE_KEY {
+        return accounts_from_value(value.clone()).map(|_| ());
+    } else if key.starts_with(PENDING_MUTATIONS_STORAGE_KEY) {
+        check_type::<Vec<PendingMutation>>(value)
+    } else if key.starts_with(ARTICLE_DRAFTS_STORAGE_KEY) {
+        check_type::<Vec<ArticleDraft>>(value)
+    } else if key.starts_with(COMMENT_DRAFTS_STORAGE_KEY) {
+        check_type::<Vec<CommentDraft>>(value)
+    } else {
+        debug_assert!(key.starts_with(PREFERENCES_STORAGE_KEY));
+        check_type::<Preferences>(value)
+    };
+    result.map_err(|message| Error::InvalidItem {
+        key: key.to_owned(),
+        message,
+    })
+}
+
+fn check_type<T: DeserializeOwned>(value: &Value) -> Result<(), String> {
+    serde_json::from_value::<T>(value.clone())
+        .map(|_| ())
+        .map_err(|error| error.to_string())
+}
+
+/// Viewers are stored in the versioned envelope - older versions are upgraded.
+fn accounts_from_value(value: Value) -> Result<Accounts, Error> {
+    let invalid_item = |message: String| Error::InvalidItem {
+        key: STORAGE_KEY.to_owned(),
+        message,
+    };
+    let upgraded =
+        schema::upgrade(&value.to_string()).map_err(|error| invalid_item(error.to_string()))?;
+    serde_json::from_value(upgraded.data).map_err(|error| invalid_item(error.to_string()))
+}
+
+// ====== ====== TESTS ====== ======
+
+#[cfg(test)]
+pub mod tests {
+    use super::{
+        super::{
+            load_article_drafts, load_viewer, load_viewers, store_article_draft, store_viewer,
+            tests::{use_in_memory_backend, viewer},
+        },
+        *,
+    };
+    use crate::entity::DraftTarget;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn article_draft(id: u64) -> ArticleDraft {
+        ArticleDraft {
+            target: DraftTarget::New { id },
+            title: "Title".into(),
+            description: String::new(),
+            body: "Body".into(),
+            tags: String::new(),
+            edited_at_ms: 0.,
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn export_and_replace_test() {
+        // ====== ARRANGE ======
+        use_in_memory_backend();
+        store_viewer(&viewer("personal"));
+        store_article_draft(viewer("personal").username(), &article_draft(1));
+        store_api_url("http://localhost:3000/api");
+        let backup = export();
+        use_in_memory_backend();
+        store_viewer(&viewer("work"));
+
+        // ====== ACT ======
+        let result = import(&backup, ImportMode::Replace);
+
+        // ====== ASSERT ======
+        assert!(result.is_ok());
+        assert_eq!(load_viewers(), vec![viewer("personal")]);
+        assert_eq!(
+            load_article_drafts(viewer("personal").username()),
+            vec![article_draft(1)]
+        );
+        assert_eq!(load_api_url().as_deref(), Some("http://localhost:3000/api"));
+    }
+
+    #[wasm_bindgen_test]
+    fn merge_keeps_active_viewer_test() {
+        // ====== ARRANGE ======
+        use_in_memory_backend();
+        store_viewer(&viewer("personal"));
+        let backup = export();
+        use_in_memory_backend();
+        store_viewer(&viewer("work"));
+
+        // ====== ACT ======
+        let result = import(&backup, ImportMode::Merge);
+
+        // ====== ASSERT ======
+        assert!(result.is_ok());
+        assert_eq!(load_viewers(), vec![viewer("work"), viewer("personal")]);
+        assert_eq!(load_viewer(), Some(viewer("work")));
+    }
+
+    #[wasm_bindgen_test]
+    fn invalid_backup_changes_nothing_test() {
+        // ====== ARRANGE ======
+        use_in_memory_backend();
+        store_viewer(&viewer("work"));
+        let backup = r#"{
+            "format": "conduit-backup",
+            "version": 1,
+            "api_url": null,
+            "items": {"conduit_preferences": {"theme": "Neon"}}
+        }"#;
+
+        // ====== ACT ======
+        let result = import(backup, ImportMode::Replace);
+
+        // ====== ASSERT ======
+        assert!(matches!(result, Err(Error::InvalidItem { .. })));
+        assert_eq!(load_viewer(), Some(viewer("work")));
+    }
+
+    #[wasm_bindgen_test]
+    fn unsupported_backups_test() {
+        // ====== ACT & ASSERT ======
+        assert!(matches!(
+            parse(r#"{"format":"other","version":1,"api_url":null,"items":{}}"#),
+            Err(Error::UnsupportedFormat)
+        ));
+        assert!(matches!(
+            parse(r#"{"format":"conduit-backup","version":2,"api_url":null,"items":{}}"#),
+            Err(Error::UnsupportedVersion(2))
+        ));
+        assert!(matches!(
+            parse(r#"{"format":"conduit-backup","version":1,"api_url":null,"items":{"foo":1}}"#),
+            Err(Error::InvalidItem { .. })
+        ));
+    }
+}
@@ -0,0 +1,244 @@
+use serde_json::{json, Value};
+use std::fmt;
+
+/// `MIGRATIONS[n]` converts data of version `n` to version `n + 1`.
+///
+/// Never change existing migrations - add a new one and a test for it instead.
+const MIGRATIONS: &[Migration] = &[v0_to_v1, v1_to_v2];
+
+type Migration = fn(Value) -> Result<Value, String>;
+
+#[allow(clippy::cast_possible_truncation)]
+pub const CURRENT_VERSION: u32 = MIGRATIONS.len() as u32;
+
+// ------ Error ------
+
+#[derive(Debug)]
+pub enum Error {
+    Json(serde_json::Error),
+    /// Data have been stored by a newer version of the app.
+    UnsupportedVersion(u64),
+    Migration {
+        from_version: u32,
+        message: String,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Json(error) => write!(f, "invalid JSON: {}", error),
+            Self::UnsupportedVersion(version) => write!(f, "unsupported version {}", version),
+            Self::Migration {
+                from_version,
+                message,
+            } => write!(
+                f,
+                "migration from version {} failed: {}",
+                from_version, message
+            ),
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Json(error)
+    }
+}
+
+// ------ Upgraded ------
+
+pub struct Upgraded {
+    /// Version of the data before the upgrade.
+    pub from_version: u32,
+    pub data: Value,
+}
+
+/// Parse the stored item and run all migrations from its version to `CURRENT_VERSION`.
+///
+/// Items without the envelope (`{"version": .., "data": ..}`) are version `0`.
+pub fn upgrade(serialized_item: &str) -> Result<Upgraded, Error> {
+    let value: Value = serde_json::from_str(serialized_item)?;
+    let (from_version, mut data) = match value.get("version").and_then(Value::as_u64) {
+        Some(version) if version > u64::from(CURRENT_VERSION) => {
+            return Err(Error::UnsupportedVersion(version))
+        }
+        #[allow(clippy::cast_possible_truncation)]
+        Some(version) => (
+            version as u32,
+            value.get("data").cloned().unwrap_or(Value::Null),
+        ),
+        None => (0, value),
+    };
+
+    for (version, migration) in MIGRATIONS.iter().enumerate().skip(from_version as usize) {
+        #[allow(clippy::cast_possible_truncation)]
+        let version = version as u32;
+        data = migration(data).map_err(|message| Error::Migration {
+            from_version: version,
+            message,
+        })?;
+    }
+    Ok(Upgraded { from_version, data })
+}
+
+/// Wrap the data into the envelope with `CURRENT_VERSION`.
+pub fn wrap(data: Value) -> Value {
+    json!({
+        "version": CURRENT_VERSION,
+        "data": data,
+    })
+}
+
+// ====== PRIVATE ======
+
+/// Version 0 is a bare serialized `Viewer` - only the envelope has been introduced.
+fn v0_to_v1(data: Value) -> Result<Value, String> {
+    if data.get("auth_token").is_some() {
+        Ok(data)
+    } else {
+        Err("viewer without auth token".into())
+    }
+}
+
+/// Version 2 holds all signed-in viewers and the username of the active one.
+fn v1_to_v2(viewer: Value) -> Result<Value, String> {
+    let username = viewer
+        .pointer("/profile/username")
+        .cloned()
+        .ok_or("viewer without username")?;
+    Ok(json!({
+        "active": username,
+        "viewers": [viewer],
+    }))
+}
+
+// ====== ====== TESTS ====== ======
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    fn viewer_v0() -> Value {
+        json!({
+            "profile": {"bio": null, "avatar": null, "username": "John"},
+            "auth_token": "token",
+        })
+    }
+
+    fn accounts_v2() -> Value {
+        json!({
+            "active": "John",
+            "viewers": [viewer_v0()],
+        })
+    }
+
+    #[wasm_bindgen_test]
+    fn v0_to_v1_test() {
+        // ====== ACT ======
+        let data = v0_to_v1(viewer_v0());
+
+        // ====== ASSERT ======
+        assert_eq!(data, Ok(viewer_v0()));
+    }
+
+    #[wasm_bindgen_test]
+    fn v1_to_v2_test() {
+        // ====== ACT ======
+        let data = v1_to_v2(viewer_v0());
+
+        // ====== ASSERT ======
+        assert_eq!(data, Ok(accounts_v2()));
+    }
+
+    #[wasm_bindgen_test]
+    fn v1_to_v2_invalid_viewer_test() {
+        // ====== ACT ======
+        let data = v1_to_v2(json!({"auth_token": "token"}));
+
+        // ====== ASSERT ======
+        assert!(data.is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn unversioned_data_test() {
+        // ====== ARRANGE ======
+        let serialized_item = viewer_v0().to_string();
+
+        // ====== ACT ======
+        let upgraded = upgrade(&serialized_item).expect("upgrade failed");
+
+        // ====== ASSERT ======
+        assert_eq!(upgraded.from_version, 0);
+        assert_eq!(upgraded.data, accounts_v2());
+    }
+
+    #[wasm_bindgen_test]
+    fn v1_data_test() {
+        // ====== ARRANGE ======
+        let serialized_item = json!({"version": 1, "data": viewer_v0()}).to_string();
+
+        // ====== ACT ======
+        let upgraded = upgrade(&serialized_item).expect("upgrade failed");
+
+        // ====== ASSERT ======
+        assert_eq!(upgraded.from_version, 1);
+        assert_eq!(upgraded.data, accounts_v2());
+    }
+
+    #[wasm_bindgen_test]
+    fn v0_to_v1_invalid_viewer_test() {
+        // ====== ARRANGE ======
+        let serialized_item = json!({"profile": null}).to_string();
+
+        // ====== ACT ======
+        let result = upgrade(&serialized_item);
+
+        // ====== ASSERT ======
+        assert!(matches!(
+            result,
+            Err(Error::Migration {
+                from_version: 0,
+                ..
+            })
+        ));
+    }
+
+    #[wasm_bindgen_test]
+    fn current_version_test() {
+        // ====== ARRANGE ======
+        let serialized_item = wrap(accounts_v2()).to_string();
+
+        // ====== ACT ======
+        let upgraded = upgrade(&serialized_item).expect("upgrade failed");
+
+        // ====== ASSERT ======
+        assert_eq!(upgraded.from_version, CURRENT_VERSION);
+        assert_eq!(upgraded.data, accounts_v2());
+    }
+
+    #[wasm_bindgen_test]
+    fn unsupported_version_test() {
+        // ====== ARRANGE ======
+        let serialized_item = json!({"version": CURRENT_VERSION + 1, "data": null}).to_string();
+
+        // ====== ACT ======
+        let result = upgrade(&serialized_item);
+
+        // ====== ASSERT ======
+        assert!(matches!(result, Err(Error::UnsupportedVersion(_))));
+    }
+
+    #[wasm_bindgen_test]
+    fn invalid_json_test() {
+        // ====== ACT ======
+        let result = upgrade("{not json");
+
+        // ====== ASSERT ======
+        assert!(matches!(result, Err(Error::Json(_))));
+    }
+}